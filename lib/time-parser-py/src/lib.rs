@@ -9,11 +9,20 @@ mod durations {
 }
 
 #[pyfunction]
-fn parse_timedelta(interval: &str) -> PyResult<PyObject> {
+#[pyo3(signature = (interval, default_unit = None))]
+fn parse_timedelta(interval: &str, default_unit: Option<&str>) -> PyResult<PyObject> {
+    let default_unit = match default_unit {
+        Some(unit) => Some(
+            unit.parse::<durations::Unit>()
+                .map_err(|_| pyo3::exceptions::PyValueError::new_err("Invalid unit"))?,
+        ),
+        None => None,
+    };
     Python::with_gil(|py: Python<'_>| {
         let datetime = PyModule::import(py, "datetime")?;
         let timedelta = datetime.getattr("timedelta")?;
-        match durations::parse_duration(interval) {
+        let parser = durations::DurationParser::new().default_unit(default_unit);
+        match parser.parse(interval) {
             Ok(duration) => {
                 // Import the `datetime` module and create a `timedelta` object
                 const HOURS: i64 = 0;
@@ -9,9 +9,10 @@ class ParseTimeDeltaTc:
     input: str = "1d"
     expected: timedelta | None = timedelta(days=1)
     raises: Exception | None = None
+    default_unit: str | None = None
 
     def _validate(self):
-        actual = time_parser.parse_timedelta(self.input)
+        actual = time_parser.parse_timedelta(self.input, default_unit=self.default_unit)
         if self.expected is not None:
             assert actual == self.expected
         else:
@@ -29,10 +30,16 @@ parse_timedelta_happy_paths = [
     ParseTimeDeltaTc(),
     ParseTimeDeltaTc("1w2d3h4m5s", timedelta(weeks=1, days=2, hours=3, minutes=4, seconds=5)),
     ParseTimeDeltaTc("1w2d3h4m5s6ms7us", timedelta(days=9, seconds=11045, microseconds=6007)),
+    ParseTimeDeltaTc("30", timedelta(seconds=30), default_unit="s"),
+    ParseTimeDeltaTc("30", timedelta(milliseconds=30), default_unit="ms"),
+    ParseTimeDeltaTc("1.5", timedelta(seconds=1, milliseconds=500), default_unit="seconds"),
+    ParseTimeDeltaTc("2m", timedelta(minutes=2), default_unit="ms"),
 ]
 
 parse_timedelta_sad_paths = [
     ParseTimeDeltaTc(input="invalid", raises=ValueError),
+    ParseTimeDeltaTc(input="30", raises=ValueError),
+    ParseTimeDeltaTc(input="30", raises=ValueError, default_unit="fortnights"),
 ]
 
 parse_timedelta_test_cases = parse_timedelta_happy_paths + parse_timedelta_sad_paths
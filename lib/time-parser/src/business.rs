@@ -0,0 +1,435 @@
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use chrono::{Datelike, Duration as ChronoDuration, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+
+use crate::durations::parse_business_duration;
+use crate::icalendar::{parse_ical_date_value, unfold_lines};
+use crate::{parse_recurrence_set, Error, Result};
+
+/// How many days in a row without working time are searched before giving up, so a calendar with no working hours
+/// cannot loop forever.
+const MAX_IDLE_DAYS: u32 = 366;
+
+/// Weekly working hours plus a list of holidays, for measuring time in business days and working hours.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WorkCalendar {
+    hours: [Vec<(NaiveTime, NaiveTime)>; 7],
+    holidays: BTreeSet<NaiveDate>,
+}
+
+impl Default for WorkCalendar {
+    /// Monday to Friday, 09:00 to 17:00, with no holidays.
+    fn default() -> Self {
+        let mut calendar = Self::empty();
+        let office_hours = (NaiveTime::from_hms_opt(9, 0, 0), NaiveTime::from_hms_opt(17, 0, 0));
+        let (Some(open), Some(close)) = office_hours else {
+            unreachable!("Office hours must be valid");
+        };
+        for weekday in [Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri] {
+            calendar.hours[weekday.num_days_from_monday() as usize] = vec![(open, close)];
+        }
+        calendar
+    }
+}
+
+impl WorkCalendar {
+    /// A calendar with no working hours and no holidays.
+    pub fn empty() -> Self {
+        Self {
+            hours: Default::default(),
+            holidays: BTreeSet::new(),
+        }
+    }
+
+    /// Sets the working windows for a weekday, replacing any previous ones.  Each window runs from its start up to,
+    /// but not including, its end; windows must not overlap.
+    pub fn set_hours(&mut self, weekday: Weekday, windows: &[(NaiveTime, NaiveTime)]) -> Result<()> {
+        let mut windows = windows.to_vec();
+        windows.sort();
+        for (index, (open, close)) in windows.iter().enumerate() {
+            let overlaps_previous = index > 0 && windows[index - 1].1 > *open;
+            if close <= open || overlaps_previous {
+                tracing::trace!("Invalid working window: {}-{}", open, close);
+                return Err(Error::invalid_time(format!("{}-{}", open, close)));
+            }
+        }
+        self.hours[weekday.num_days_from_monday() as usize] = windows;
+        Ok(())
+    }
+
+    pub fn add_holiday(&mut self, date: NaiveDate) -> bool {
+        self.holidays.insert(date)
+    }
+
+    /// Loads holidays from lines of `YYYY-MM-DD`, each optionally followed by a name.  Blank lines and lines starting
+    /// with `#` are skipped.  Returns how many new holidays were added.
+    pub fn load_holidays_text(&mut self, text: impl AsRef<str>) -> Result<usize> {
+        let mut added = 0;
+        for line in text.as_ref().lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let date = line.split_whitespace().next().unwrap_or_default();
+            let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_e| {
+                tracing::trace!("Invalid holiday line: {}", line);
+                Error::invalid_time(line)
+            })?;
+            added += self.add_holiday(date) as usize;
+        }
+        Ok(added)
+    }
+
+    /// Loads holidays from the `VEVENT`s of an iCalendar file.
+    ///
+    /// Each event covers the days from its `DTSTART` up to its exclusive `DTEND`, or a single day without one.
+    /// Recurring events are expanded with their `RRULE` and `EXDATE`s through `through`.  Returns how many new holidays
+    /// were added.
+    pub fn load_holidays_ics(&mut self, ics: impl AsRef<str>, through: NaiveDate) -> Result<usize> {
+        let mut added = 0;
+        let mut event: Option<Vec<String>> = None;
+
+        for line in unfold_lines(ics.as_ref()) {
+            let line = line.trim();
+            if line.eq_ignore_ascii_case("BEGIN:VEVENT") {
+                event = Some(Vec::new());
+            } else if line.eq_ignore_ascii_case("END:VEVENT") {
+                if let Some(lines) = event.take() {
+                    for date in event_dates(&lines, through)? {
+                        added += self.add_holiday(date) as usize;
+                    }
+                }
+            } else if let Some(lines) = event.as_mut() {
+                lines.push(line.to_string());
+            }
+        }
+
+        Ok(added)
+    }
+
+    pub fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.holidays.contains(&date)
+    }
+
+    /// Whether `date` has any working time.
+    pub fn is_business_day(&self, date: NaiveDate) -> bool {
+        !self.windows(date).is_empty()
+    }
+
+    /// Adds a quantity such as `3bd`, `3 business days`, `8h` or `1bd4h` to `start` in business time.
+    ///
+    /// Business days move to the same time of day on the n-th following business day.  The remaining duration is then
+    /// counted in working hours only.
+    pub fn add(&self, start: NaiveDateTime, quantity: impl AsRef<str>) -> Result<NaiveDateTime> {
+        let quantity = quantity.as_ref();
+        let (business_days, duration) = parse_business_duration(quantity)?;
+        self.add_business_days(start, business_days)
+            .and_then(|datetime| self.add_business_time(datetime, duration))
+            .ok_or_else(|| {
+                tracing::trace!("No working time to add {} to {}", quantity, start);
+                Error::error(format!("no working time to add {} to {}", quantity, start))
+            })
+    }
+
+    /// Moves to the same time of day on the `days`-th business day after `start`.
+    ///
+    /// Returns `None` when no business day can be found.
+    pub fn add_business_days(&self, start: NaiveDateTime, days: u64) -> Option<NaiveDateTime> {
+        let mut date = start.date();
+        let (mut remaining, mut idle_days) = (days, 0);
+        while remaining > 0 {
+            date = date.succ_opt()?;
+            if self.is_business_day(date) {
+                remaining -= 1;
+                idle_days = 0;
+            } else {
+                idle_days += 1;
+                if idle_days > MAX_IDLE_DAYS {
+                    return None;
+                }
+            }
+        }
+        Some(date.and_time(start.time()))
+    }
+
+    /// Adds `duration` of working time to `start`.  A result at the end of a window stays there rather than moving to
+    /// the next opening, so 09:00 plus eight hours is 17:00 on the same day.
+    ///
+    /// Returns `None` when the calendar runs out of working time.
+    pub fn add_business_time(&self, start: NaiveDateTime, duration: Duration) -> Option<NaiveDateTime> {
+        let mut remaining = ChronoDuration::from_std(duration).ok()?;
+        if remaining.is_zero() {
+            return Some(start);
+        }
+
+        let (mut date, mut idle_days) = (start.date(), 0);
+        loop {
+            let mut worked = false;
+            for &(open, close) in self.windows(date) {
+                let window_start = date.and_time(open).max(start);
+                let window_end = date.and_time(close);
+                if window_end <= window_start {
+                    continue;
+                }
+                let available = window_end - window_start;
+                if remaining <= available {
+                    return window_start.checked_add_signed(remaining);
+                }
+                remaining -= available;
+                worked = true;
+            }
+
+            idle_days = if worked { 0 } else { idle_days + 1 };
+            if idle_days > MAX_IDLE_DAYS {
+                return None;
+            }
+            date = date.succ_opt()?;
+        }
+    }
+
+    /// The working time between `start` and `end`, or zero when `end` is not after `start`.
+    pub fn business_time_between(&self, start: NaiveDateTime, end: NaiveDateTime) -> Duration {
+        let mut total = ChronoDuration::zero();
+        let mut date = start.date();
+        while date <= end.date() {
+            for &(open, close) in self.windows(date) {
+                let window_start = date.and_time(open).max(start);
+                let window_end = date.and_time(close).min(end);
+                if window_end > window_start {
+                    total += window_end - window_start;
+                }
+            }
+            let Some(next) = date.succ_opt() else {
+                break;
+            };
+            date = next;
+        }
+        total.to_std().unwrap_or_default()
+    }
+
+    #[inline(always)]
+    fn windows(&self, date: NaiveDate) -> &[(NaiveTime, NaiveTime)] {
+        if self.is_holiday(date) {
+            return &[];
+        }
+        &self.hours[date.weekday().num_days_from_monday() as usize]
+    }
+}
+
+/// The holiday dates of one `VEVENT`, given its content lines.
+#[inline(always)]
+fn event_dates(lines: &[String], through: NaiveDate) -> Result<Vec<NaiveDate>> {
+    let event = lines.join("\n");
+    let (mut dtstart, mut dtend, mut recurring) = (None, None, false);
+
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let name = name
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_uppercase();
+        match name.as_str() {
+            "DTSTART" => dtstart = Some(parse_ical_date_value(value, false, &event)?),
+            "DTEND" => dtend = Some(parse_ical_date_value(value, false, &event)?),
+            "RRULE" => recurring = true,
+            _ => {}
+        }
+    }
+
+    let Some(dtstart) = dtstart else {
+        tracing::trace!("Holiday event without DTSTART: {}", event);
+        return Err(Error::invalid_recurrence(event));
+    };
+    let days = dtend.map_or(1, |dtend| (dtend.date() - dtstart.date()).num_days().max(1));
+
+    let starts = if recurring {
+        parse_recurrence_set(&event)?
+            .iter()
+            .map(|occurrence| occurrence.date())
+            .take_while(|date| *date <= through)
+            .collect()
+    } else {
+        vec![dtstart.date()]
+    };
+
+    Ok(starts
+        .into_iter()
+        .flat_map(|start| (0..days).filter_map(move |offset| start.checked_add_signed(ChronoDuration::days(offset))))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    fn datetime(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").expect("datetime must be valid for this test")
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").expect("date must be valid for this test")
+    }
+
+    fn calendar() -> WorkCalendar {
+        let mut calendar = WorkCalendar::default();
+        calendar
+            .load_holidays_text("# company holidays\n2024-12-25 Christmas Day\n\n2024-12-26 Boxing Day\n")
+            .expect("holidays must be valid for this test");
+        calendar
+    }
+
+    // 2024-12-20 is a Friday
+    #[rstest]
+    #[case::business_days("2024-12-20 15:00", "3bd", "2024-12-27 15:00")]
+    #[case::business_days_words("2024-12-20 15:00", "3 business days", "2024-12-27 15:00")]
+    #[case::working_days("2024-12-24 10:00", "1 working day", "2024-12-27 10:00")]
+    #[case::hours_same_day("2024-12-20 09:00", "8h", "2024-12-20 17:00")]
+    #[case::working_hours_over_weekend("2024-12-20 15:00", "8 working hours", "2024-12-23 15:00")]
+    #[case::hours_over_holidays("2024-12-24 16:00", "2h", "2024-12-27 10:00")]
+    #[case::before_opening("2024-12-20 07:00", "1h30m", "2024-12-20 10:30")]
+    #[case::on_weekend("2024-12-21 12:00", "30m", "2024-12-23 09:30")]
+    #[case::days_then_hours("2024-12-20 16:00", "1bd2h", "2024-12-24 10:00")]
+    #[case::hms("2024-12-20 16:00", "02:00:00", "2024-12-23 10:00")]
+    #[case::zero("2024-12-21 12:00", "0h", "2024-12-21 12:00")]
+    fn test_add(#[case] start: &str, #[case] quantity: &str, #[case] expected: &str) {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .try_init()
+            .ok();
+
+        match calendar().add(datetime(start), quantity) {
+            Ok(actual) => assert_eq!(actual, datetime(expected)),
+            Err(e) => panic!("Expected Ok, got Err: {:?}", e),
+        }
+    }
+
+    #[rstest]
+    #[case::text("3 days later")]
+    #[case::unknown_unit("3 fortnights")]
+    #[case::empty("")]
+    fn test_add_invalid(#[case] quantity: &str) {
+        match calendar().add(datetime("2024-12-20 09:00"), quantity) {
+            Ok(actual) => panic!("Expected Err, got Ok {:?}", actual),
+            Err(e) => assert_eq!(e.to_string(), Error::invalid_duration(quantity).to_string()),
+        }
+    }
+
+    #[rstest]
+    #[case::days("3d")]
+    #[case::working_week("1 working week")]
+    #[case::business_weeks("2 business weeks")]
+    fn test_add_calendar_units(#[case] quantity: &str) {
+        match calendar().add(datetime("2024-01-05 10:00"), quantity) {
+            Ok(actual) => panic!("Expected Err, got Ok {:?}", actual),
+            Err(e) => assert!(
+                e.to_string()
+                    .contains("has no working-time meaning"),
+                "{}",
+                e
+            ),
+        }
+    }
+
+    #[test]
+    fn test_add_without_working_time() {
+        let result = WorkCalendar::empty().add(datetime("2024-12-20 09:00"), "1h");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_business_days_are_not_plain_durations() {
+        assert!(crate::parse_duration("3bd").is_err());
+    }
+
+    #[rstest]
+    #[case::same_window("2024-12-20 10:00", "2024-12-20 12:30", Duration::from_secs(9000))]
+    #[case::over_weekend("2024-12-20 15:00", "2024-12-23 11:00", Duration::from_secs(4 * 3600))]
+    #[case::over_holidays("2024-12-24 00:00", "2024-12-28 00:00", Duration::from_secs(16 * 3600))]
+    #[case::outside_hours("2024-12-20 18:00", "2024-12-21 08:00", Duration::ZERO)]
+    #[case::reversed("2024-12-23 10:00", "2024-12-20 10:00", Duration::ZERO)]
+    fn test_business_time_between(#[case] start: &str, #[case] end: &str, #[case] expected: Duration) {
+        assert_eq!(calendar().business_time_between(datetime(start), datetime(end)), expected);
+    }
+
+    #[test]
+    fn test_split_shifts() {
+        let time = |hour| NaiveTime::from_hms_opt(hour, 0, 0).expect("time must be valid for this test");
+        let mut calendar = WorkCalendar::empty();
+        calendar
+            .set_hours(Weekday::Mon, &[(time(13), time(17)), (time(8), time(12))])
+            .expect("windows must be valid for this test");
+
+        assert_eq!(
+            calendar
+                .add(datetime("2024-12-23 11:00"), "2h")
+                .ok(),
+            Some(datetime("2024-12-23 14:00"))
+        );
+        assert_eq!(
+            calendar
+                .add(datetime("2024-12-23 16:00"), "2h")
+                .ok(),
+            Some(datetime("2024-12-30 09:00"))
+        );
+        assert!(calendar
+            .set_hours(Weekday::Tue, &[(time(8), time(12)), (time(11), time(13))])
+            .is_err());
+        assert!(calendar
+            .set_hours(Weekday::Tue, &[(time(12), time(8))])
+            .is_err());
+    }
+
+    #[test]
+    fn test_load_holidays_ics() {
+        let ics = "BEGIN:VCALENDAR\r\n\
+                   BEGIN:VEVENT\r\n\
+                   SUMMARY:New Year\r\n\
+                   DTSTART;VALUE=DATE:20240101\r\n\
+                   RRULE:FREQ=YEARLY;BYMONTH=1;BYMONTHDAY=1\r\n\
+                   END:VEVENT\r\n\
+                   BEGIN:VEVENT\r\n\
+                   SUMMARY:Company retreat\r\n\
+                   DTSTART;VALUE=DATE:20240603\r\n\
+                   DTEND;VALUE=DATE:20240605\r\n\
+                   END:VEVENT\r\n\
+                   END:VCALENDAR\r\n";
+        let mut calendar = WorkCalendar::default();
+        assert_eq!(
+            calendar
+                .load_holidays_ics(ics, date("2026-12-31"))
+                .ok(),
+            Some(5)
+        );
+
+        for holiday in ["2024-01-01", "2025-01-01", "2026-01-01", "2024-06-03", "2024-06-04"] {
+            assert!(calendar.is_holiday(date(holiday)), "{} must be a holiday", holiday);
+        }
+        assert!(!calendar.is_holiday(date("2027-01-01")));
+        assert!(!calendar.is_holiday(date("2024-06-05")));
+    }
+
+    #[rstest]
+    #[case::text_invalid_date("2024-13-01 Nope")]
+    #[case::text_no_date("Christmas")]
+    fn test_load_holidays_text_invalid(#[case] text: &str) {
+        let result = WorkCalendar::default().load_holidays_text(text);
+        match result {
+            Ok(actual) => panic!("Expected Err, got Ok {:?}", actual),
+            Err(e) => assert_eq!(e.to_string(), Error::invalid_time(text).to_string()),
+        }
+    }
+
+    #[test]
+    fn test_load_holidays_ics_without_dtstart() {
+        let ics = "BEGIN:VEVENT\nSUMMARY:Someday\nEND:VEVENT\n";
+        assert!(WorkCalendar::default()
+            .load_holidays_ics(ics, date("2024-12-31"))
+            .is_err());
+    }
+}
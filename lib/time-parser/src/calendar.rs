@@ -0,0 +1,154 @@
+use std::fmt;
+use std::time::Duration;
+
+use chrono::{Datelike, Duration as ChronoDuration, Months, NaiveDate, NaiveDateTime};
+
+use crate::duration_as_string;
+
+pub(crate) fn days_in_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .and_then(|date| date.pred_opt())
+        .map(|date| date.day())
+        .unwrap_or(31)
+}
+
+pub(crate) fn days_in_year(year: i32) -> u32 {
+    if NaiveDate::from_ymd_opt(year, 2, 29).is_some() {
+        366
+    } else {
+        365
+    }
+}
+
+/// A difference between two points in time in calendar units: years, months, days, then a time of less than a day.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CalendarDuration {
+    /// Whether the end came before the start.
+    pub negative: bool,
+    pub years: u32,
+    pub months: u32,
+    pub days: u32,
+    pub time: Duration,
+}
+
+impl fmt::Display for CalendarDuration {
+    /// Formats as `2y3mo5d4h30m`, using `mo` for months so they are not read as minutes, and `0s` when there is no
+    /// difference.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = String::new();
+        if self.years > 0 {
+            parts.push_str(&format!("{}y", self.years));
+        }
+        if self.months > 0 {
+            parts.push_str(&format!("{}mo", self.months));
+        }
+        if self.days > 0 {
+            parts.push_str(&format!("{}d", self.days));
+        }
+        parts.push_str(&duration_as_string(&self.time));
+
+        match (parts.is_empty(), self.negative) {
+            (true, _) => f.write_str("0s"),
+            (false, true) => write!(f, "-{}", parts),
+            (false, false) => f.write_str(&parts),
+        }
+    }
+}
+
+/// Computes the calendar difference from `start` to `end`, accepting dates or naive datetimes.
+///
+/// Months are counted first: the result holds the largest number of whole months that can be added to `start` without
+/// passing `end`, where adding months to a day that does not exist in the target month clamps to that month's last
+/// day.  So 2024-01-31 to 2024-02-29 is `1mo`, 2024-01-31 to 2024-03-01 is `1mo1d`, and 2024-02-29 to 2025-02-28 is
+/// `1y`.  The remainder is split into whole days and a time of day.
+///
+/// When `end` is before `start`, the result is the difference from `end` to `start` with `negative` set.  Timezone-aware
+/// values should be converted to one zone and passed as `naive_local()`.
+#[inline(always)]
+pub fn calendar_difference(start: impl Into<NaiveDateTime>, end: impl Into<NaiveDateTime>) -> CalendarDuration {
+    let (start, end) = (start.into(), end.into());
+    if end < start {
+        return CalendarDuration {
+            negative: true,
+            ..calendar_difference(end, start)
+        };
+    }
+
+    let mut months = (end.year() - start.year()) * 12 + end.month() as i32 - start.month() as i32;
+    let mut anchor = add_months(start, months);
+    if anchor.is_none_or(|anchor| anchor > end) {
+        months -= 1;
+        anchor = add_months(start, months);
+    }
+    let Some(anchor) = anchor else {
+        unreachable!("Months between two valid datetimes must be addable");
+    };
+
+    let remainder = end - anchor;
+    let days = remainder.num_days();
+    let time = (remainder - ChronoDuration::days(days))
+        .to_std()
+        .expect("the remainder after whole days is never negative");
+
+    CalendarDuration {
+        negative: false,
+        years: (months / 12) as u32,
+        months: (months % 12) as u32,
+        days: days as u32,
+        time,
+    }
+}
+
+#[inline(always)]
+fn add_months(datetime: NaiveDateTime, months: i32) -> Option<NaiveDateTime> {
+    datetime.checked_add_months(Months::new(months as u32))
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    fn datetime(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").expect("datetime must be valid for this test")
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").expect("date must be valid for this test")
+    }
+
+    #[rstest]
+    #[case::same_day("2024-01-01", "2024-01-01", (0, 0, 0), "0s")]
+    #[case::years_months_days("2021-10-13", "2024-01-18", (2, 3, 5), "2y3mo5d")]
+    #[case::month_end_clamps("2024-01-31", "2024-02-29", (0, 1, 0), "1mo")]
+    #[case::month_end_then_day("2024-01-31", "2024-03-01", (0, 1, 1), "1mo1d")]
+    #[case::short_month_to_long_month("2024-02-29", "2024-03-31", (0, 1, 2), "1mo2d")]
+    #[case::leap_day_anniversary("2024-02-29", "2025-02-28", (1, 0, 0), "1y")]
+    #[case::leap_day_to_march("2024-02-29", "2025-03-01", (1, 0, 1), "1y1d")]
+    #[case::day_before_month_end("2024-01-30", "2024-02-28", (0, 0, 29), "29d")]
+    #[case::year_boundary("2023-12-15", "2024-01-14", (0, 0, 30), "30d")]
+    fn test_calendar_difference_dates(#[case] start: &str, #[case] end: &str, #[case] expected: (u32, u32, u32), #[case] formatted: &str) {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .try_init()
+            .ok();
+
+        let difference = calendar_difference(date(start), date(end));
+        assert_eq!((difference.years, difference.months, difference.days), expected);
+        assert_eq!(difference.time, Duration::ZERO);
+        assert!(!difference.negative);
+        assert_eq!(difference.to_string(), formatted);
+    }
+
+    #[rstest]
+    #[case::with_time("2024-01-01 09:00:00", "2024-02-03 17:30:15", "1mo2d8h30m15s")]
+    #[case::time_borrows_a_day("2024-01-01 18:00:00", "2024-01-03 06:00:00", "1d12h")]
+    #[case::time_borrows_a_month("2024-01-15 12:00:00", "2024-02-15 11:00:00", "30d23h")]
+    #[case::negative("2024-03-01 00:00:00", "2024-01-31 00:00:00", "-1mo1d")]
+    #[case::less_than_a_day("2024-01-01 23:00:00", "2024-01-02 01:00:00", "2h")]
+    fn test_calendar_difference_datetimes(#[case] start: &str, #[case] end: &str, #[case] formatted: &str) {
+        assert_eq!(calendar_difference(datetime(start), datetime(end)).to_string(), formatted);
+    }
+}
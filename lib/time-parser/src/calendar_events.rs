@@ -0,0 +1,602 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Datelike, Duration as ChronoDuration, LocalResult, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike, Weekday};
+use chrono_tz::Tz;
+use pest::iterators::Pair;
+use pest::Parser;
+use pest_derive::Parser;
+
+use crate::calendar::days_in_month;
+use crate::{Error, Result};
+
+const MICROSECONDS_PER_SECOND: u32 = 1_000_000;
+const MIN_YEAR: u32 = 1970;
+const MAX_YEAR: u32 = 2199;
+const MAX_DST_SHIFT_HOURS: i64 = 3;
+const WEEKDAY_NAMES: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+#[derive(Parser)]
+#[grammar = "grammars/calendar_events.pest"]
+struct CalendarEventParser;
+
+/// One entry of a calendar event component: a value, a `start..stop` range or a `start/repeat` repetition.
+///
+/// Seconds are stored in microseconds so fractional seconds survive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct CalendarComponent {
+    start: u32,
+    stop: Option<u32>,
+    repeat: Option<u32>,
+}
+
+impl CalendarComponent {
+    fn matches(&self, value: u32) -> bool {
+        let in_range = value >= self.start && self.stop.is_none_or(|stop| value <= stop);
+        match (self.repeat, self.stop) {
+            (Some(repeat), _) => in_range && (value - self.start).is_multiple_of(repeat),
+            (None, Some(_)) => in_range,
+            (None, None) => value == self.start,
+        }
+    }
+}
+
+/// A systemd calendar event, as used by `OnCalendar=` (see `systemd.time(7)`).
+///
+/// An empty component list is a wildcard.  Elapse times are computed in floating local time, unless the event ends
+/// with a timezone: then the components are matched in that zone and elapse times are in UTC.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CalendarEvent {
+    weekdays: u8,
+    years: Vec<CalendarComponent>,
+    months: Vec<CalendarComponent>,
+    days: Vec<CalendarComponent>,
+    end_of_month: bool,
+    hours: Vec<CalendarComponent>,
+    minutes: Vec<CalendarComponent>,
+    seconds: Vec<CalendarComponent>,
+    timezone: Option<Tz>,
+}
+
+impl CalendarEvent {
+    /// The first elapse strictly after `after`.  Both are UTC when the event has a timezone.
+    ///
+    /// Wall-clock times skipped by a DST transition do not elapse.
+    pub fn next_elapse(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let Some(timezone) = self.timezone else {
+            return self.next_local_elapse(after);
+        };
+
+        // Start early enough that a wall-clock time repeated by a DST transition is not missed.
+        let local_after = timezone.from_utc_datetime(&after).naive_local();
+        let mut local = local_after
+            .checked_sub_signed(ChronoDuration::hours(MAX_DST_SHIFT_HOURS))
+            .unwrap_or(local_after);
+        loop {
+            local = self.next_local_elapse(local)?;
+            let instants = match timezone.from_local_datetime(&local) {
+                LocalResult::Single(instant) => vec![instant],
+                LocalResult::Ambiguous(earlier, later) => vec![earlier, later],
+                LocalResult::None => Vec::new(),
+            };
+            if let Some(elapse) = instants
+                .into_iter()
+                .map(|instant| instant.naive_utc())
+                .find(|elapse| *elapse > after)
+            {
+                return Some(elapse);
+            }
+        }
+    }
+
+    /// The first elapse strictly after `after`, in the event's own wall-clock time.
+    fn next_local_elapse(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut date = after.date();
+        let mut after_time = Some(after.time());
+
+        while date.year() as u32 <= MAX_YEAR {
+            if !matches_any(&self.years, date.year() as u32) {
+                date = NaiveDate::from_ymd_opt(date.year() + 1, 1, 1)?;
+                after_time = None;
+                continue;
+            }
+            if !matches_any(&self.months, date.month()) {
+                date = date
+                    .with_day(1)?
+                    .checked_add_months(Months::new(1))?;
+                after_time = None;
+                continue;
+            }
+            if self.matches_day(date) {
+                if let Some(time) = self.next_time(after_time) {
+                    return Some(date.and_time(time));
+                }
+            }
+            date = date.succ_opt()?;
+            after_time = None;
+        }
+        None
+    }
+
+    /// Successive elapses strictly after `after`.
+    pub fn elapses(&self, after: NaiveDateTime) -> Elapses {
+        Elapses {
+            event: self.clone(),
+            after: Some(after),
+        }
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let day = if self.end_of_month {
+            days_in_month(date.year(), date.month()) - date.day() + 1
+        } else {
+            date.day()
+        };
+        self.weekdays & (1 << date.weekday().num_days_from_monday()) != 0 && matches_any(&self.days, day)
+    }
+
+    fn next_time(&self, after: Option<NaiveTime>) -> Option<NaiveTime> {
+        let hours = expand(&self.hours, 0, 23, 1);
+        let minutes = expand(&self.minutes, 0, 59, 1);
+        let seconds = expand(&self.seconds, 0, 60 * MICROSECONDS_PER_SECOND - 1, MICROSECONDS_PER_SECOND);
+        for &hour in &hours {
+            if after.is_some_and(|after| hour < after.hour()) {
+                continue;
+            }
+            for &minute in &minutes {
+                for &microseconds in &seconds {
+                    let time = NaiveTime::from_hms_micro_opt(hour, minute, microseconds / MICROSECONDS_PER_SECOND, microseconds % MICROSECONDS_PER_SECOND)?;
+                    if after.is_none_or(|after| time > after) {
+                        return Some(time);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+impl FromStr for CalendarEvent {
+    type Err = Error;
+
+    fn from_str(expression: &str) -> Result<Self> {
+        parse_calendar_event(expression)
+    }
+}
+
+/// Formats the event in the normalized form printed by `systemd-analyze calendar`.
+impl fmt::Display for CalendarEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.weekdays != ALL_WEEKDAYS {
+            format_weekdays(f, self.weekdays)?;
+            f.write_str(" ")?;
+        }
+        format_components(f, &self.years, 4, false)?;
+        f.write_str("-")?;
+        format_components(f, &self.months, 2, false)?;
+        f.write_str(if self.end_of_month { "~" } else { "-" })?;
+        format_components(f, &self.days, 2, false)?;
+        f.write_str(" ")?;
+        format_components(f, &self.hours, 2, false)?;
+        f.write_str(":")?;
+        format_components(f, &self.minutes, 2, false)?;
+        f.write_str(":")?;
+        format_components(f, &self.seconds, 2, true)?;
+        if let Some(timezone) = &self.timezone {
+            write!(f, " {}", timezone.name())?;
+        }
+        Ok(())
+    }
+}
+
+const ALL_WEEKDAYS: u8 = 0b111_1111;
+
+fn format_weekdays(f: &mut fmt::Formatter<'_>, weekdays: u8) -> fmt::Result {
+    let mut first = true;
+    let mut day = 0;
+    while day < 7 {
+        if weekdays & (1 << day) == 0 {
+            day += 1;
+            continue;
+        }
+        let mut last = day;
+        while last + 1 < 7 && weekdays & (1 << (last + 1)) != 0 {
+            last += 1;
+        }
+        if !first {
+            f.write_str(",")?;
+        }
+        first = false;
+        f.write_str(WEEKDAY_NAMES[day])?;
+        match last - day {
+            0 => {}
+            1 => write!(f, ",{}", WEEKDAY_NAMES[last])?,
+            _ => write!(f, "..{}", WEEKDAY_NAMES[last])?,
+        }
+        day = last + 1;
+    }
+    Ok(())
+}
+
+fn format_components(f: &mut fmt::Formatter<'_>, components: &[CalendarComponent], width: usize, microseconds: bool) -> fmt::Result {
+    let format_value = |f: &mut fmt::Formatter<'_>, value: u32| {
+        if microseconds {
+            write!(f, "{:0width$}", value / MICROSECONDS_PER_SECOND, width = width)?;
+            if !value.is_multiple_of(MICROSECONDS_PER_SECOND) {
+                write!(f, ".{:06}", value % MICROSECONDS_PER_SECOND)?;
+            }
+            Ok(())
+        } else {
+            write!(f, "{:0width$}", value, width = width)
+        }
+    };
+
+    if components.is_empty() {
+        return f.write_str("*");
+    }
+    for (index, component) in components.iter().enumerate() {
+        if index > 0 {
+            f.write_str(",")?;
+        }
+        format_value(f, component.start)?;
+        if let Some(stop) = component.stop {
+            f.write_str("..")?;
+            format_value(f, stop)?;
+        }
+        if let Some(repeat) = component.repeat {
+            if microseconds {
+                write!(f, "/{}", repeat / MICROSECONDS_PER_SECOND)?;
+                if !repeat.is_multiple_of(MICROSECONDS_PER_SECOND) {
+                    write!(f, ".{:06}", repeat % MICROSECONDS_PER_SECOND)?;
+                }
+            } else {
+                write!(f, "/{}", repeat)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Parses a systemd calendar event such as `Mon..Fri *-*-* 09:00:00`, `*-*-01 00:00:00`, `hourly` or `Sat,Sun 10:00`.
+#[inline(always)]
+pub fn parse_calendar_event(expression: impl AsRef<str>) -> Result<CalendarEvent> {
+    let expression = expression.as_ref();
+    let parse_result = CalendarEventParser::parse(Rule::calendar_event, expression).map_err(|_e| Error::invalid_recurrence(expression))?;
+
+    let zero = vec![CalendarComponent { start: 0, stop: None, repeat: None }];
+    let first = vec![CalendarComponent { start: 1, stop: None, repeat: None }];
+    let mut event = CalendarEvent {
+        weekdays: ALL_WEEKDAYS,
+        years: Vec::new(),
+        months: Vec::new(),
+        days: Vec::new(),
+        end_of_month: false,
+        hours: zero.clone(),
+        minutes: zero.clone(),
+        seconds: zero.clone(),
+        timezone: None,
+    };
+
+    for pair in parse_result.flat_map(|pair| pair.into_inner()) {
+        match pair.as_rule() {
+            Rule::cal_shorthand => {
+                let shorthand = pair.as_str().to_ascii_lowercase();
+                let months = |values: &[u32]| {
+                    values
+                        .iter()
+                        .map(|&start| CalendarComponent { start, stop: None, repeat: None })
+                        .collect()
+                };
+                match shorthand.as_str() {
+                    "minutely" => (event.hours, event.minutes) = (Vec::new(), Vec::new()),
+                    "hourly" => event.hours = Vec::new(),
+                    "daily" => {}
+                    "weekly" => event.weekdays = 1,
+                    "monthly" => event.days = first.clone(),
+                    "yearly" | "annually" => (event.months, event.days) = (first.clone(), first.clone()),
+                    "quarterly" => (event.months, event.days) = (months(&[1, 4, 7, 10]), first.clone()),
+                    "semiannually" => (event.months, event.days) = (months(&[1, 7]), first.clone()),
+                    _ => unreachable!("Unexpected calendar shorthand: {:?}", shorthand),
+                }
+            }
+            Rule::cal_weekdays => event.weekdays = parse_weekdays(pair, expression)?,
+            Rule::cal_date => {
+                for inner_pair in pair.into_inner() {
+                    match inner_pair.as_rule() {
+                        Rule::cal_year => event.years = parse_components(inner_pair, MIN_YEAR, MAX_YEAR, false, expression)?,
+                        Rule::cal_month => event.months = parse_components(inner_pair, 1, 12, false, expression)?,
+                        Rule::cal_day => event.days = parse_components(inner_pair, 1, 31, false, expression)?,
+                        Rule::cal_last_day => {
+                            event.days = parse_components(inner_pair, 1, 31, false, expression)?;
+                            event.end_of_month = true;
+                        }
+                        _ => unreachable!("Unexpected rule in calendar date parsing: {:?}", inner_pair.as_rule()),
+                    }
+                }
+            }
+            Rule::cal_time => {
+                for inner_pair in pair.into_inner() {
+                    match inner_pair.as_rule() {
+                        Rule::cal_hour => event.hours = parse_components(inner_pair, 0, 23, false, expression)?,
+                        Rule::cal_minute => event.minutes = parse_components(inner_pair, 0, 59, false, expression)?,
+                        Rule::cal_second => event.seconds = parse_components(inner_pair, 0, 60 * MICROSECONDS_PER_SECOND - 1, true, expression)?,
+                        _ => unreachable!("Unexpected rule in calendar time parsing: {:?}", inner_pair.as_rule()),
+                    }
+                }
+            }
+            Rule::cal_timezone => {
+                let name = pair.as_str();
+                let timezone = if name.eq_ignore_ascii_case("UTC") { Ok(Tz::UTC) } else { name.parse::<Tz>() };
+                let Ok(timezone) = timezone else {
+                    tracing::trace!("Unknown timezone: {}", name);
+                    return Err(Error::invalid_recurrence(expression));
+                };
+                event.timezone = Some(timezone);
+            }
+            Rule::EOI => {}
+            _ => unreachable!("Unexpected rule in calendar event parsing: {:?}", pair.as_rule()),
+        }
+    }
+
+    Ok(event)
+}
+
+#[inline(always)]
+fn parse_weekdays(pair: Pair<Rule>, expression: &str) -> Result<u8> {
+    let mut weekdays = 0;
+    for item in pair.into_inner() {
+        let days: Vec<u32> = item
+            .into_inner()
+            .map(|day| weekday_from_rule(day.as_rule()).num_days_from_monday())
+            .collect();
+        let (first, last) = match days.as_slice() {
+            [day] => (*day, *day),
+            [first, last] => (*first, *last),
+            _ => unreachable!("Weekday item must be a day or a range"),
+        };
+        if last < first {
+            tracing::trace!("Weekday range must not wrap: {}", expression);
+            return Err(Error::invalid_recurrence(expression));
+        }
+        for day in first..=last {
+            weekdays |= 1 << day;
+        }
+    }
+    Ok(weekdays)
+}
+
+#[inline(always)]
+fn weekday_from_rule(rule: Rule) -> Weekday {
+    match rule {
+        Rule::cal_monday => Weekday::Mon,
+        Rule::cal_tuesday => Weekday::Tue,
+        Rule::cal_wednesday => Weekday::Wed,
+        Rule::cal_thursday => Weekday::Thu,
+        Rule::cal_friday => Weekday::Fri,
+        Rule::cal_saturday => Weekday::Sat,
+        Rule::cal_sunday => Weekday::Sun,
+        _ => unreachable!("Unexpected rule in weekday parsing: {:?}", rule),
+    }
+}
+
+#[inline(always)]
+fn parse_components(pair: Pair<Rule>, min: u32, max: u32, microseconds: bool, expression: &str) -> Result<Vec<CalendarComponent>> {
+    let mut components = Vec::new();
+
+    for item in pair.into_inner() {
+        let mut component = CalendarComponent { start: min, stop: None, repeat: None };
+        let mut wildcard = false;
+        for inner_pair in item.into_inner() {
+            match inner_pair.as_rule() {
+                Rule::cal_wildcard => wildcard = true,
+                Rule::cal_value => component.start = parse_value(inner_pair.as_str(), microseconds, expression)?,
+                Rule::cal_stop => component.stop = Some(parse_value(&inner_pair.as_str()[2..], microseconds, expression)?),
+                Rule::cal_repeat => component.repeat = Some(parse_value(&inner_pair.as_str()[1..], microseconds, expression)?),
+                _ => unreachable!("Unexpected rule in calendar component parsing: {:?}", inner_pair.as_rule()),
+            }
+        }
+
+        // A bare wildcard matches everything, and one wildcard in a list makes the whole list a wildcard
+        if wildcard && component.stop.is_none() && component.repeat.is_none() {
+            return Ok(Vec::new());
+        }
+        let out_of_range = component.start < min
+            || component.start > max
+            || component
+                .stop
+                .is_some_and(|stop| stop < component.start || stop > max);
+        if out_of_range || component.repeat == Some(0) {
+            tracing::trace!("Calendar component out of range {}..{}: {:?}", min, max, component);
+            return Err(Error::invalid_recurrence(expression));
+        }
+        components.push(component);
+    }
+
+    components.sort_unstable();
+    components.dedup();
+    Ok(components)
+}
+
+#[inline(always)]
+fn parse_value(value: &str, microseconds: bool, expression: &str) -> Result<u32> {
+    let invalid = || {
+        tracing::trace!("Invalid calendar value: {}", value);
+        Error::invalid_recurrence(expression)
+    };
+
+    let (whole, fraction) = value.split_once('.').unwrap_or((value, ""));
+    let whole = whole.parse::<u32>().map_err(|_e| invalid())?;
+    if !microseconds {
+        return if fraction.is_empty() { Ok(whole) } else { Err(invalid()) };
+    }
+    if fraction.len() > 6 {
+        return Err(invalid());
+    }
+    let fraction = format!("{:0<6}", fraction)
+        .parse::<u32>()
+        .map_err(|_e| invalid())?;
+    whole
+        .checked_mul(MICROSECONDS_PER_SECOND)
+        .and_then(|whole| whole.checked_add(fraction))
+        .ok_or_else(invalid)
+}
+
+fn matches_any(components: &[CalendarComponent], value: u32) -> bool {
+    components.is_empty()
+        || components
+            .iter()
+            .any(|component| component.matches(value))
+}
+
+fn expand(components: &[CalendarComponent], min: u32, max: u32, step: u32) -> Vec<u32> {
+    let mut values: Vec<u32> = if components.is_empty() {
+        (min..=max).step_by(step as usize).collect()
+    } else {
+        components
+            .iter()
+            .flat_map(|component| {
+                let stop = if component.repeat.is_some() {
+                    component.stop.unwrap_or(max)
+                } else {
+                    component.stop.unwrap_or(component.start)
+                };
+                (component.start..=stop.min(max)).step_by(component.repeat.unwrap_or(step) as usize)
+            })
+            .collect()
+    };
+    values.sort_unstable();
+    values.dedup();
+    values
+}
+
+/// Iterator over the elapses of a [`CalendarEvent`].
+#[derive(Clone, Debug)]
+pub struct Elapses {
+    event: CalendarEvent,
+    after: Option<NaiveDateTime>,
+}
+
+impl Iterator for Elapses {
+    type Item = NaiveDateTime;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let elapse = self.event.next_elapse(self.after?);
+        self.after = elapse;
+        elapse
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    fn datetime(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f").expect("datetime must be valid for this test")
+    }
+
+    #[rstest]
+    #[case::weekday_range("Mon..Fri *-*-* 09:00:00", "Mon..Fri *-*-* 09:00:00")]
+    #[case::first_of_month("*-*-01 00:00:00", "*-*-01 00:00:00")]
+    #[case::weekend_time_only("Sat,Sun 10:00", "Sat,Sun *-*-* 10:00:00")]
+    #[case::minutely("minutely", "*-*-* *:*:00")]
+    #[case::hourly("hourly", "*-*-* *:00:00")]
+    #[case::daily("daily", "*-*-* 00:00:00")]
+    #[case::weekly("weekly", "Mon *-*-* 00:00:00")]
+    #[case::monthly("monthly", "*-*-01 00:00:00")]
+    #[case::yearly("yearly", "*-01-01 00:00:00")]
+    #[case::annually("annually", "*-01-01 00:00:00")]
+    #[case::quarterly("quarterly", "*-01,04,07,10-01 00:00:00")]
+    #[case::semiannually("semiannually", "*-01,07-01 00:00:00")]
+    #[case::weekday_only("Wed", "Wed *-*-* 00:00:00")]
+    #[case::long_weekday_names("monday,tuesday,wednesday", "Mon..Wed *-*-* 00:00:00")]
+    #[case::weekday_pairs("Mon,Tue,Thu,Sat,Sun", "Mon,Tue,Thu,Sat,Sun *-*-* 00:00:00")]
+    #[case::month_day("12-25", "*-12-25 00:00:00")]
+    #[case::full_date("2024-1-5 8:5", "2024-01-05 08:05:00")]
+    #[case::repetition("*-*-* */15:00", "*-*-* 00/15:00:00")]
+    #[case::range_with_repetition("*-*-* 8..18/2:30", "*-*-* 08..18/2:30:00")]
+    #[case::list_is_sorted("*-*-* 18,06,12:00", "*-*-* 06,12,18:00:00")]
+    #[case::last_day("*-02~03", "*-02~03 00:00:00")]
+    #[case::fractional_seconds("*:*:0/0.5", "*-*-* *:*:00/0.500000")]
+    #[case::timezone("daily UTC", "*-*-* 00:00:00 UTC")]
+    #[case::iana_timezone("Mon 09:00 Europe/Berlin", "Mon *-*-* 09:00:00 Europe/Berlin")]
+    #[case::extra_whitespace("  Sat,Sun   10:00  ", "Sat,Sun *-*-* 10:00:00")]
+    fn test_parse_calendar_event(#[case] expression: &str, #[case] expected: &str) {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .try_init()
+            .ok();
+
+        let result = parse_calendar_event(expression);
+        match result {
+            Ok(event) => {
+                assert_eq!(event.to_string(), expected);
+                assert_eq!(parse_calendar_event(expected).expect("normalized form must parse"), event);
+            }
+            Err(e) => panic!("Expected Ok, got Err: {:?}", e),
+        }
+    }
+
+    #[rstest]
+    #[case::invalid_month("*-13-01")]
+    #[case::invalid_hour("24:00")]
+    #[case::invalid_weekday("Funday 10:00")]
+    #[case::wrapping_weekday_range("Fri..Mon")]
+    #[case::reversed_range("*-*-* 10..08:00")]
+    #[case::zero_repetition("*-*-* */0:00")]
+    #[case::fractional_minute("*-*-* 10:30.5")]
+    #[case::unknown_timezone("daily Mars")]
+    #[case::unknown_iana_timezone("*-*-* 09:00 Mars/Base")]
+    #[case::empty("")]
+    fn test_parse_calendar_event_invalid(#[case] expression: &str) {
+        let result = parse_calendar_event(expression);
+        match result {
+            Ok(actual) => panic!("Expected Err, got Ok {:?}", actual),
+            Err(e) => assert_eq!(e.to_string(), Error::invalid_recurrence(expression).to_string()),
+        }
+    }
+
+    #[rstest]
+    #[case::weekdays_at_nine("Mon..Fri *-*-* 09:00:00", "2024-01-05 09:00:00", &["2024-01-08 09:00:00", "2024-01-09 09:00:00"])]
+    #[case::first_of_month("*-*-01 00:00:00", "2024-01-15 12:00:00", &["2024-02-01 00:00:00", "2024-03-01 00:00:00"])]
+    #[case::hourly("hourly", "2024-01-01 10:30:00", &["2024-01-01 11:00:00", "2024-01-01 12:00:00"])]
+    #[case::weekend("Sat,Sun 10:00", "2024-01-06 10:00:00", &["2024-01-07 10:00:00", "2024-01-13 10:00:00"])]
+    #[case::repetition("*-*-* 08..18/4:00", "2024-01-01 16:00:00", &["2024-01-02 08:00:00", "2024-01-02 12:00:00", "2024-01-02 16:00:00"])]
+    #[case::last_day_of_february("*-02~01", "2023-03-01 00:00:00", &["2024-02-29 00:00:00", "2025-02-28 00:00:00"])]
+    #[case::leap_day("*-02-29", "2024-03-01 00:00:00", &["2028-02-29 00:00:00"])]
+    #[case::fractional_seconds("*:*:0/0.5", "2024-01-01 00:00:59", &["2024-01-01 00:00:59.500", "2024-01-01 00:01:00"])]
+    #[case::fixed_year_in_past("2020-*-* 00:00", "2024-01-01 00:00:00", &[])]
+    #[case::weekday_and_date("Fri *-*-13", "2024-01-01 00:00:00", &["2024-09-13 00:00:00", "2024-12-13 00:00:00"])]
+    fn test_calendar_event_elapses(#[case] expression: &str, #[case] after: &str, #[case] expected: &[&str]) {
+        let event = parse_calendar_event(expression).expect("calendar event must be valid for this test");
+        let result: Vec<NaiveDateTime> = event
+            .elapses(datetime(after))
+            .take(expected.len())
+            .collect();
+        let expected: Vec<NaiveDateTime> = expected
+            .iter()
+            .map(|value| datetime(value))
+            .collect();
+        assert_eq!(result, expected);
+    }
+
+    #[rstest]
+    #[case::utc("daily UTC", "2024-01-01 12:00:00", &["2024-01-02 00:00:00"])]
+    #[case::winter("*-*-* 09:00 Europe/Berlin", "2024-01-01 00:00:00", &["2024-01-01 08:00:00", "2024-01-02 08:00:00"])]
+    #[case::summer("*-*-* 09:00 Europe/Berlin", "2024-07-01 00:00:00", &["2024-07-01 07:00:00"])]
+    #[case::skipped_by_dst("*-*-* 02:30 Europe/Berlin", "2024-03-30 12:00:00", &["2024-04-01 00:30:00"])]
+    #[case::repeated_by_dst("*-*-* 02:30 Europe/Berlin", "2024-10-27 00:40:00", &["2024-10-27 01:30:00", "2024-10-28 01:30:00"])]
+    fn test_calendar_event_elapses_zoned(#[case] expression: &str, #[case] after: &str, #[case] expected: &[&str]) {
+        let event = parse_calendar_event(expression).expect("calendar event must be valid for this test");
+        let result: Vec<NaiveDateTime> = event
+            .elapses(datetime(after))
+            .take(expected.len())
+            .collect();
+        let expected: Vec<NaiveDateTime> = expected
+            .iter()
+            .map(|value| datetime(value))
+            .collect();
+        assert_eq!(result, expected);
+    }
+}
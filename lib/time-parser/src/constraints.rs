@@ -0,0 +1,178 @@
+use std::time::Duration;
+
+use crate::{Error, HumanDuration, Result, Unit};
+
+/// Bounds a parsed duration must satisfy, such as "between 1s and 1h and a multiple of 100ms".
+///
+/// Attach one to a [`crate::DurationParser`] or pass it to [`crate::parse_duration_bounded`].  Every check is optional
+/// and the default accepts anything.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DurationConstraint {
+    /// The shortest allowed duration, inclusive.
+    pub min: Option<Duration>,
+    /// The longest allowed duration, inclusive.
+    pub max: Option<Duration>,
+    /// The units that may be written in shorthand or used as the default unit of a bare number.  HMS is not affected.
+    pub units: Option<Vec<Unit>>,
+    /// The duration must be a whole multiple of this.  Zero is ignored.
+    pub granularity: Option<Duration>,
+}
+
+impl DurationConstraint {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn min(mut self, min: Duration) -> Self {
+        self.min = Some(min);
+        self
+    }
+
+    pub fn max(mut self, max: Duration) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    pub fn units(mut self, units: impl IntoIterator<Item = Unit>) -> Self {
+        self.units = Some(units.into_iter().collect());
+        self
+    }
+
+    pub fn granularity(mut self, granularity: Duration) -> Self {
+        self.granularity = Some(granularity);
+        self
+    }
+
+    /// Returns `duration` when it is within the bounds and a multiple of the granularity.
+    pub fn check(&self, duration: Duration, interval: &str) -> Result<Duration> {
+        if let Some(min) = self.min.filter(|min| duration < *min) {
+            tracing::trace!("Duration {:?} below minimum {:?}: {}", duration, min, interval);
+            return Err(Error::error(format!(
+                "duration {} is less than the minimum {}: {}",
+                HumanDuration(duration),
+                HumanDuration(min),
+                interval
+            )));
+        }
+        if let Some(max) = self.max.filter(|max| duration > *max) {
+            tracing::trace!("Duration {:?} above maximum {:?}: {}", duration, max, interval);
+            return Err(Error::error(format!(
+                "duration {} is more than the maximum {}: {}",
+                HumanDuration(duration),
+                HumanDuration(max),
+                interval
+            )));
+        }
+        if let Some(granularity) = self
+            .granularity
+            .filter(|granularity| !granularity.is_zero())
+        {
+            if !duration
+                .as_nanos()
+                .is_multiple_of(granularity.as_nanos())
+            {
+                tracing::trace!("Duration {:?} not a multiple of {:?}: {}", duration, granularity, interval);
+                return Err(Error::error(format!(
+                    "duration {} is not a multiple of {}: {}",
+                    HumanDuration(duration),
+                    HumanDuration(granularity),
+                    interval
+                )));
+            }
+        }
+        Ok(duration)
+    }
+
+    /// Checks a unit written in shorthand against the whitelist.
+    pub(crate) fn check_unit(&self, unit: Unit, interval: &str) -> Result<()> {
+        let Some(units) = self
+            .units
+            .as_ref()
+            .filter(|units| !units.contains(&unit))
+        else {
+            return Ok(());
+        };
+        let expected: Vec<_> = units.iter().map(Unit::symbol).collect();
+        tracing::trace!("Unit {} not in {:?}: {}", unit, expected, interval);
+        Err(Error::error(format!("unit {} is not allowed, expected one of {}: {}", unit, expected.join(", "), interval)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    fn config_timeout() -> DurationConstraint {
+        DurationConstraint::new()
+            .min(Duration::from_secs(1))
+            .max(Duration::from_secs(3600))
+            .granularity(Duration::from_millis(100))
+    }
+
+    #[rstest]
+    #[case::minimum("1s", Duration::from_secs(1))]
+    #[case::maximum("1h", Duration::from_secs(3600))]
+    #[case::multiple("1s500ms", Duration::from_millis(1500))]
+    #[case::hms("00:30:00", Duration::from_secs(1800))]
+    fn test_parse_duration_bounded(#[case] interval: &str, #[case] expected: Duration) {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .try_init()
+            .ok();
+
+        match crate::parse_duration_bounded(interval, &config_timeout()) {
+            Ok(actual) => assert_eq!(actual, expected),
+            Err(e) => panic!("Expected Ok, got Err: {:?}", e),
+        }
+    }
+
+    #[rstest]
+    #[case::below_minimum(config_timeout(), "500ms", "duration 500ms is less than the minimum 1s: 500ms")]
+    #[case::above_maximum(config_timeout(), "1h1s", "duration 1h1s is more than the maximum 1h: 1h1s")]
+    #[case::granularity(config_timeout(), "1s50ms", "duration 1s50ms is not a multiple of 100ms: 1s50ms")]
+    #[case::hms_above_maximum(config_timeout(), "02:00:00", "duration 2h is more than the maximum 1h: 02:00:00")]
+    #[case::unit(DurationConstraint::new().units([Unit::Hours, Unit::Minutes]), "1h30s", "unit s is not allowed, expected one of h, m: 1h30s")]
+    #[case::unit_alias(DurationConstraint::new().units([Unit::Seconds]), "2 minutes", "unit m is not allowed, expected one of s: 2 minutes")]
+    #[case::invalid_duration(config_timeout(), "soon", "invalid duration")]
+    fn test_parse_duration_bounded_invalid(#[case] constraint: DurationConstraint, #[case] interval: &str, #[case] expected: &str) {
+        match crate::parse_duration_bounded(interval, &constraint) {
+            Ok(actual) => panic!("Expected Err, got Ok {:?}", actual),
+            Err(e) => assert_eq!(e.to_string(), expected),
+        }
+    }
+
+    #[test]
+    fn test_zero_granularity() {
+        let constraint = DurationConstraint::new().granularity(Duration::ZERO);
+        assert_eq!(
+            constraint
+                .check(Duration::from_nanos(7), "7ns")
+                .ok(),
+            Some(Duration::from_nanos(7))
+        );
+    }
+
+    #[test]
+    fn test_parser_constraint() {
+        let parser = crate::DurationParser::new()
+            .default_unit(Unit::Seconds)
+            .constraint(config_timeout());
+        assert_eq!(parser.parse("1.5").ok(), Some(Duration::from_millis(1500)));
+        assert!(parser.parse("0.5").is_err());
+        assert_eq!(parser.parse_all("wait 2s then 10m").ok(), Some(vec![Duration::from_secs(2), Duration::from_secs(600)]));
+        assert!(parser.parse_all("wait 2s then 2h").is_err());
+    }
+
+    #[test]
+    fn test_parser_constraint_default_unit() {
+        let parser = crate::DurationParser::new()
+            .constraint(DurationConstraint::new().units([Unit::Hours]))
+            .default_unit(Unit::Seconds);
+        match parser.parse("30") {
+            Ok(actual) => panic!("Expected Err, got Ok {:?}", actual),
+            Err(e) => assert_eq!(e.to_string(), "unit s is not allowed, expected one of h: 30"),
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use std::fmt;
+use std::time::Duration;
+
+use pest::Parser;
+use pest_derive::Parser;
+
+use crate::durations::parse_duration;
+use crate::{Error, Result, Unit};
+
+#[derive(Parser)]
+#[grammar = "grammars/intervals.pest"]
+#[grammar = "grammars/conversions.pest"]
+struct ConversionParser;
+
+/// A duration expressed in a unit, kept as an exact fraction of that unit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Conversion {
+    numerator: u128,
+    denominator: u128,
+    unit: Unit,
+}
+
+impl Conversion {
+    /// Expresses `duration` as a multiple of `unit`.
+    pub fn new(duration: Duration, unit: Unit) -> Self {
+        let (numerator, denominator) = (duration.as_nanos(), unit.nanoseconds() as u128);
+        let divisor = gcd(numerator, denominator);
+        Self {
+            numerator: numerator / divisor,
+            denominator: denominator / divisor,
+            unit,
+        }
+    }
+
+    /// The value as a fraction in lowest terms.
+    pub fn ratio(&self) -> (u128, u128) {
+        (self.numerator, self.denominator)
+    }
+
+    pub fn unit(&self) -> Unit {
+        self.unit
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denominator == 1
+    }
+
+    pub fn as_f64(&self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
+    }
+
+    /// The value with exactly `precision` decimal places, rounding half away from zero.
+    pub fn to_decimal(&self, precision: usize) -> String {
+        let integer = self.numerator / self.denominator;
+        let mut remainder = self.numerator % self.denominator;
+
+        let mut digits = Vec::with_capacity(precision);
+        for _ in 0..precision {
+            remainder *= 10;
+            digits.push((remainder / self.denominator) as u8);
+            remainder %= self.denominator;
+        }
+
+        let mut integer = integer;
+        if remainder * 2 >= self.denominator {
+            let carried = digits.iter_mut().rev().all(|digit| {
+                *digit = (*digit + 1) % 10;
+                *digit == 0
+            });
+            if carried {
+                integer += 1;
+            }
+        }
+
+        let fraction: String = digits
+            .iter()
+            .map(|digit| char::from(b'0' + digit))
+            .collect();
+        if fraction.is_empty() {
+            integer.to_string()
+        } else {
+            format!("{}.{}", integer, fraction)
+        }
+    }
+
+    /// The number of decimal places needed to write the value exactly, or `None` when its decimal expansion repeats.
+    fn exact_decimal_places(&self) -> Option<usize> {
+        let (mut denominator, mut twos, mut fives) = (self.denominator, 0, 0);
+        while denominator % 2 == 0 {
+            denominator /= 2;
+            twos += 1;
+        }
+        while denominator % 5 == 0 {
+            denominator /= 5;
+            fives += 1;
+        }
+        (denominator == 1).then_some(usize::max(twos, fives))
+    }
+}
+
+/// Formats the value without its unit.
+///
+/// With a precision (`{:.3}`) the value is rounded to that many decimal places.  Otherwise it is written exactly: as
+/// a decimal when it terminates (`1.5`) and as a fraction when it repeats (`1/60`).
+impl fmt::Display for Conversion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(precision) = f.precision() {
+            return f.write_str(&self.to_decimal(precision));
+        }
+        match self.exact_decimal_places() {
+            Some(places) => f.write_str(&self.to_decimal(places)),
+            None => write!(f, "{}/{}", self.numerator, self.denominator),
+        }
+    }
+}
+
+/// Answers a conversion query of the form `<duration> in|as|to <unit>`, such as `90m in hours` or `1w2d as seconds`.
+///
+/// The duration is anything [`crate::parse_duration`] accepts and the unit is any [`Unit`] alias.
+#[inline(always)]
+pub fn convert_duration(query: impl AsRef<str>) -> Result<Conversion> {
+    let query = query.as_ref();
+    let parse_result = ConversionParser::parse(Rule::conversion, query.trim()).map_err(|_e| Error::invalid_duration(query))?;
+
+    let (mut quantity, mut target) = ("", "");
+    for pair in parse_result.flat_map(|pair| pair.into_inner()) {
+        match pair.as_rule() {
+            Rule::conv_quantity => quantity = pair.as_str(),
+            Rule::conv_target => target = pair.as_str(),
+            Rule::EOI => {}
+            _ => unreachable!("Unexpected rule in conversion parsing: {:?}", pair.as_rule()),
+        }
+    }
+
+    let duration = parse_duration(quantity)?;
+    let unit = target.parse::<Unit>()?;
+    tracing::trace!("Converting {:?} to {}: {}", duration, unit, query);
+    Ok(Conversion::new(duration, unit))
+}
+
+#[inline(always)]
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::minutes_in_hours("90m in hours", "1.5", "h")]
+    #[case::week_as_seconds("1w as seconds", "604800", "s")]
+    #[case::compound("1w2d to s", "777600", "s")]
+    #[case::repeating("1m in hours", "1/60", "h")]
+    #[case::to_milliseconds("1s500ms in ms", "1500", "ms")]
+    #[case::to_microseconds("1ms as us", "1000", "µs")]
+    #[case::to_nanoseconds("1us as nanoseconds", "1000", "ns")]
+    #[case::to_weeks("10d in weeks", "10/7", "w")]
+    #[case::to_days("36h in days", "1.5", "d")]
+    #[case::to_minutes("01:30:00 in min", "90", "m")]
+    #[case::sub_unit("250ms in seconds", "0.25", "s")]
+    #[case::uppercase_keyword("2h IN minutes", "120", "m")]
+    #[case::zero("0s in hours", "0", "h")]
+    fn test_convert_duration(#[case] query: &str, #[case] expected: &str, #[case] unit: &str) {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .try_init()
+            .ok();
+
+        match convert_duration(query) {
+            Ok(actual) => {
+                assert_eq!(actual.to_string(), expected);
+                assert_eq!(actual.unit().symbol(), unit);
+            }
+            Err(e) => panic!("Expected Ok, got Err: {:?}", e),
+        }
+    }
+
+    #[rstest]
+    #[case::one_third("20m in hours", 3, "0.333")]
+    #[case::two_thirds("40m in hours", 3, "0.667")]
+    #[case::carry_into_integer("119s in minutes", 1, "2.0")]
+    #[case::no_places("90m in hours", 0, "2")]
+    #[case::padded("90m in hours", 4, "1.5000")]
+    fn test_to_decimal(#[case] query: &str, #[case] precision: usize, #[case] expected: &str) {
+        let conversion = convert_duration(query).expect("query must be valid for this test");
+        assert_eq!(conversion.to_decimal(precision), expected);
+        assert_eq!(format!("{:.*}", precision, conversion), expected);
+    }
+
+    #[test]
+    fn test_ratio() {
+        let conversion = convert_duration("10d in weeks").expect("query must be valid for this test");
+        assert_eq!(conversion.ratio(), (10, 7));
+        assert!(!conversion.is_integer());
+        assert!((conversion.as_f64() - 10.0 / 7.0).abs() < f64::EPSILON);
+    }
+
+    #[rstest]
+    #[case::empty("")]
+    #[case::missing_unit("90m in")]
+    #[case::missing_keyword("90m hours")]
+    #[case::unknown_unit("90m in fortnights")]
+    #[case::missing_quantity("in hours")]
+    #[case::trailing_text("90m in hours please")]
+    fn test_convert_duration_invalid(#[case] query: &str) {
+        match convert_duration(query) {
+            Ok(actual) => panic!("Expected Err, got Ok {:?}", actual),
+            Err(e) => assert_eq!(e.to_string(), Error::invalid_duration(query).to_string()),
+        }
+    }
+}
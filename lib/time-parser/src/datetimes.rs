@@ -0,0 +1,556 @@
+use std::fmt;
+
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Weekday};
+use pest::iterators::Pair;
+use pest::Parser;
+use pest_derive::Parser;
+
+use crate::timezones::default_abbreviations;
+use crate::{Clock, Error, Result, SystemClock, TimeZoneAbbreviations, SECONDS_PER_HOUR, SECONDS_PER_MINUTE};
+
+#[derive(Parser)]
+#[grammar = "grammars/datetimes.pest"]
+struct DateTimeParser;
+
+/// The format a datetime string was recognized as.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DateTimeFormat {
+    /// `2024-10-18T12:00:01Z`, `2024-10-18 12:00:01.5+02:00`
+    Rfc3339,
+    /// `2024-10-18`, `2024-10-18T12:00`, `2024-10-18T12:00:01+0200`
+    Iso8601Extended,
+    /// `20241018`, `20241018T1200`, `20241018T120001Z`
+    Iso8601Basic,
+    /// `Fri, 18 Oct 2024 12:00:01 +0000`
+    Rfc2822,
+    /// `Oct 18 12:00:01`
+    Syslog,
+    /// `[18/Oct/2024:12:00:01 +0000]`, as written by Apache's Common Log Format
+    CommonLog,
+    /// `2024-10-18 12:00`, `2024/10/18 12:00:01`
+    Plain,
+}
+
+impl fmt::Display for DateTimeFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Rfc3339 => "RFC 3339",
+            Self::Iso8601Extended => "ISO 8601 extended",
+            Self::Iso8601Basic => "ISO 8601 basic",
+            Self::Rfc2822 => "RFC 2822",
+            Self::Syslog => "syslog",
+            Self::CommonLog => "Common Log Format",
+            Self::Plain => "YYYY-MM-DD HH:MM:SS",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A parsed datetime: the wall-clock time as written, its UTC offset when the input had one, and the matched format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ParsedDateTime {
+    pub datetime: NaiveDateTime,
+    pub offset: Option<FixedOffset>,
+    pub format: DateTimeFormat,
+}
+
+impl ParsedDateTime {
+    /// The offset-aware datetime, when the input included an offset.
+    pub fn with_offset(&self) -> Option<DateTime<FixedOffset>> {
+        self.offset.and_then(|offset| {
+            offset
+                .from_local_datetime(&self.datetime)
+                .single()
+        })
+    }
+
+    /// The offset-aware datetime, using `default` when the input had no offset.
+    pub fn and_offset(&self, default: FixedOffset) -> DateTime<FixedOffset> {
+        let offset = self.offset.unwrap_or(default);
+        offset
+            .from_local_datetime(&self.datetime)
+            .single()
+            .expect("fixed offsets are never ambiguous")
+    }
+}
+
+/// Parses a single point in time, detecting its format.
+///
+/// Supported formats are listed in [`DateTimeFormat`].  Syslog timestamps carry no year, so the current year of the
+/// [`SystemClock`] is used; see [`parse_datetime_with_clock`] to choose it.  Date-only inputs resolve to midnight.  RFC 2822 zone names are resolved with the default
+/// [`TimeZoneAbbreviations`].
+#[inline(always)]
+pub fn parse_datetime(input: impl AsRef<str>) -> Result<ParsedDateTime> {
+    parse_datetime_with_abbreviations(input, default_abbreviations())
+}
+
+/// Parses a datetime as [`parse_datetime`] does, resolving RFC 2822 zone names such as `PST` with `abbreviations`.
+#[inline(always)]
+pub fn parse_datetime_with_abbreviations(input: impl AsRef<str>, abbreviations: &TimeZoneAbbreviations) -> Result<ParsedDateTime> {
+    parse_datetime_with_year(input, SystemClock.now().year(), abbreviations)
+}
+
+/// Parses a datetime as [`parse_datetime`] does, taking the year of syslog timestamps from `clock`.
+#[inline(always)]
+pub fn parse_datetime_with_clock(input: impl AsRef<str>, clock: impl Clock) -> Result<ParsedDateTime> {
+    parse_datetime_with_year(input, clock.now().year(), default_abbreviations())
+}
+
+#[inline(always)]
+pub(crate) fn parse_datetime_with_year(input: impl AsRef<str>, default_year: i32, abbreviations: &TimeZoneAbbreviations) -> Result<ParsedDateTime> {
+    let input = input.as_ref();
+    let parse_result = DateTimeParser::parse(Rule::datetime, input.trim()).map_err(|_e| Error::invalid_time(input))?;
+
+    for pair in parse_result.flat_map(|pair| pair.into_inner()) {
+        let format = match pair.as_rule() {
+            Rule::rfc3339 => DateTimeFormat::Rfc3339,
+            Rule::iso8601_extended => DateTimeFormat::Iso8601Extended,
+            Rule::iso8601_basic => DateTimeFormat::Iso8601Basic,
+            Rule::rfc2822 => DateTimeFormat::Rfc2822,
+            Rule::syslog => DateTimeFormat::Syslog,
+            Rule::common_log => DateTimeFormat::CommonLog,
+            Rule::plain => DateTimeFormat::Plain,
+            _ => continue,
+        };
+        return parse_datetime_inner(pair, format, default_year, abbreviations, input);
+    }
+
+    tracing::trace!("No datetime found in input: {}", input);
+    Err(Error::invalid_time(input))
+}
+
+#[inline(always)]
+fn parse_datetime_inner(pair: Pair<Rule>, format: DateTimeFormat, default_year: i32, abbreviations: &TimeZoneAbbreviations, input: &str) -> Result<ParsedDateTime> {
+    let number = |pair: &Pair<Rule>| {
+        pair.as_str().parse::<u32>().map_err(|_e| {
+            tracing::trace!("Invalid number: {}", pair.as_str());
+            Error::invalid_time(input)
+        })
+    };
+
+    let mut year = default_year;
+    let (mut month, mut day, mut hour, mut minute, mut second, mut nanosecond) = (1, 1, 0, 0, 0, 0);
+    let mut weekday = None;
+    let mut offset = None;
+    let mut zone = None;
+
+    for inner_pair in pair.into_inner() {
+        match inner_pair.as_rule() {
+            Rule::dt_year => year = number(&inner_pair)? as i32,
+            Rule::dt_month | Rule::dt_short_month => month = number(&inner_pair)?,
+            Rule::dt_day | Rule::dt_short_day => day = number(&inner_pair)?,
+            Rule::dt_hour | Rule::dt_short_hour => hour = number(&inner_pair)?,
+            Rule::dt_minute => minute = number(&inner_pair)?,
+            Rule::dt_second => second = number(&inner_pair)?,
+            Rule::dt_fraction => nanosecond = parse_fraction(&inner_pair.as_str()[1..]),
+            Rule::dt_utc | Rule::dt_military_zone => offset = FixedOffset::east_opt(0),
+            Rule::dt_offset_extended | Rule::dt_offset_basic | Rule::dt_offset_numeric => offset = Some(parse_offset(inner_pair, input)?),
+            Rule::dt_zone_name => {
+                zone = Some(
+                    abbreviations
+                        .get(inner_pair.as_str())
+                        .ok_or_else(|| {
+                            tracing::trace!("Unknown zone name: {}", inner_pair.as_str());
+                            Error::invalid_time(input)
+                        })?,
+                )
+            }
+            rule => match month_from_rule(rule) {
+                Some(month_number) => month = month_number,
+                None => weekday = weekday_from_rule(rule),
+            },
+        }
+    }
+
+    let date = NaiveDate::from_ymd_opt(year, month, day).ok_or_else(|| {
+        tracing::trace!("Invalid date: {:04}-{:02}-{:02}", year, month, day);
+        Error::invalid_time(input)
+    })?;
+    if weekday.is_some_and(|weekday| weekday != date.weekday()) {
+        tracing::trace!("Weekday does not match date: {}", input);
+        return Err(Error::invalid_time(input));
+    }
+    let time = NaiveTime::from_hms_nano_opt(hour, minute, second, nanosecond).ok_or_else(|| {
+        tracing::trace!("Invalid time: {:02}:{:02}:{:02}", hour, minute, second);
+        Error::invalid_time(input)
+    })?;
+
+    let datetime = date.and_time(time);
+    if let Some(zone) = zone {
+        let localized = zone.localize(datetime).ok_or_else(|| {
+            tracing::trace!("Datetime does not exist in zone {}: {}", zone, input);
+            Error::invalid_time(input)
+        })?;
+        offset = Some(*localized.offset());
+    }
+
+    Ok(ParsedDateTime { datetime, offset, format })
+}
+
+/// Whether ISO 8601 dates are written with separators (`2024-W05-3`) or without (`2024W053`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum IsoDateStyle {
+    #[default]
+    Extended,
+    Basic,
+}
+
+/// Parses an ISO 8601 week date: `2024-W05-3`, `2024W053`, or `2024-W05` / `2024W05` for the Monday of that week.
+///
+/// The year is the ISO week-numbering year, which differs from the calendar year for a few days around New Year.
+#[inline(always)]
+pub fn parse_week_date(input: impl AsRef<str>) -> Result<NaiveDate> {
+    let input = input.as_ref();
+    let parse_result = DateTimeParser::parse(Rule::week_date, input.trim()).map_err(|_e| Error::invalid_time(input))?;
+
+    let (mut year, mut week, mut weekday) = (0, 0, Weekday::Mon);
+    for pair in parse_result.flat_map(|pair| pair.into_inner()) {
+        match pair.as_rule() {
+            Rule::dt_year => {
+                year = pair
+                    .as_str()
+                    .parse::<i32>()
+                    .map_err(|_e| Error::invalid_time(input))?
+            }
+            Rule::dt_week => {
+                week = pair
+                    .as_str()
+                    .parse::<u32>()
+                    .map_err(|_e| Error::invalid_time(input))?
+            }
+            Rule::dt_week_day => {
+                let day = pair
+                    .as_str()
+                    .parse::<u8>()
+                    .map_err(|_e| Error::invalid_time(input))?;
+                weekday = Weekday::try_from(day - 1).map_err(|_e| Error::invalid_time(input))?;
+            }
+            Rule::EOI => {}
+            _ => unreachable!("Unexpected rule in week date parsing: {:?}", pair.as_rule()),
+        }
+    }
+
+    NaiveDate::from_isoywd_opt(year, week, weekday).ok_or_else(|| {
+        tracing::trace!("Invalid week date: {}-W{:02}-{}", year, week, weekday.number_from_monday());
+        Error::invalid_time(input)
+    })
+}
+
+/// Parses an ISO 8601 ordinal date: `2024-123` or `2024123`.
+#[inline(always)]
+pub fn parse_ordinal_date(input: impl AsRef<str>) -> Result<NaiveDate> {
+    let input = input.as_ref();
+    let parse_result = DateTimeParser::parse(Rule::ordinal_date, input.trim()).map_err(|_e| Error::invalid_time(input))?;
+
+    let (mut year, mut ordinal) = (0, 0);
+    for pair in parse_result.flat_map(|pair| pair.into_inner()) {
+        match pair.as_rule() {
+            Rule::dt_year => {
+                year = pair
+                    .as_str()
+                    .parse::<i32>()
+                    .map_err(|_e| Error::invalid_time(input))?
+            }
+            Rule::dt_ordinal_day => {
+                ordinal = pair
+                    .as_str()
+                    .parse::<u32>()
+                    .map_err(|_e| Error::invalid_time(input))?
+            }
+            Rule::EOI => {}
+            _ => unreachable!("Unexpected rule in ordinal date parsing: {:?}", pair.as_rule()),
+        }
+    }
+
+    NaiveDate::from_yo_opt(year, ordinal).ok_or_else(|| {
+        tracing::trace!("Invalid ordinal date: {}-{:03}", year, ordinal);
+        Error::invalid_time(input)
+    })
+}
+
+/// Formats a date as an ISO 8601 week date, such as `2024-W05-3`.
+#[inline(always)]
+pub fn week_date_as_string(date: &NaiveDate, style: IsoDateStyle) -> String {
+    let week = date.iso_week();
+    let weekday = date.weekday().number_from_monday();
+    match style {
+        IsoDateStyle::Extended => format!("{:04}-W{:02}-{}", week.year(), week.week(), weekday),
+        IsoDateStyle::Basic => format!("{:04}W{:02}{}", week.year(), week.week(), weekday),
+    }
+}
+
+/// Formats a date as an ISO 8601 ordinal date, such as `2024-123`.
+#[inline(always)]
+pub fn ordinal_date_as_string(date: &NaiveDate, style: IsoDateStyle) -> String {
+    match style {
+        IsoDateStyle::Extended => format!("{:04}-{:03}", date.year(), date.ordinal()),
+        IsoDateStyle::Basic => format!("{:04}{:03}", date.year(), date.ordinal()),
+    }
+}
+
+#[inline(always)]
+pub(crate) fn parse_fraction(digits: &str) -> u32 {
+    let digits = &digits[..digits.len().min(9)];
+    let padding = 10u32.pow(9 - digits.len() as u32);
+    digits.parse::<u32>().unwrap_or(0) * padding
+}
+
+#[inline(always)]
+fn parse_offset(pair: Pair<Rule>, input: &str) -> Result<FixedOffset> {
+    let mut negative = false;
+    let mut seconds = 0;
+
+    for inner_pair in pair.into_inner() {
+        let value = inner_pair.as_str();
+        match inner_pair.as_rule() {
+            Rule::dt_offset_sign => negative = value == "-",
+            Rule::dt_offset_hour => seconds += value.parse::<i32>().unwrap_or(0) * SECONDS_PER_HOUR as i32,
+            Rule::dt_offset_minute => {
+                let minutes = value.parse::<i32>().unwrap_or(0);
+                if minutes > 59 {
+                    tracing::trace!("Invalid offset minutes: {}", value);
+                    return Err(Error::invalid_time(input));
+                }
+                seconds += minutes * SECONDS_PER_MINUTE as i32;
+            }
+            _ => unreachable!("Unexpected rule in offset parsing: {:?}", inner_pair.as_rule()),
+        }
+    }
+
+    FixedOffset::east_opt(if negative { -seconds } else { seconds }).ok_or_else(|| {
+        tracing::trace!("Offset out of range: {}", seconds);
+        Error::invalid_time(input)
+    })
+}
+
+fn month_from_rule(rule: Rule) -> Option<u32> {
+    let month = match rule {
+        Rule::dt_january => 1,
+        Rule::dt_february => 2,
+        Rule::dt_march => 3,
+        Rule::dt_april => 4,
+        Rule::dt_may => 5,
+        Rule::dt_june => 6,
+        Rule::dt_july => 7,
+        Rule::dt_august => 8,
+        Rule::dt_september => 9,
+        Rule::dt_october => 10,
+        Rule::dt_november => 11,
+        Rule::dt_december => 12,
+        _ => return None,
+    };
+    Some(month)
+}
+
+fn weekday_from_rule(rule: Rule) -> Option<Weekday> {
+    match rule {
+        Rule::dt_monday => Some(Weekday::Mon),
+        Rule::dt_tuesday => Some(Weekday::Tue),
+        Rule::dt_wednesday => Some(Weekday::Wed),
+        Rule::dt_thursday => Some(Weekday::Thu),
+        Rule::dt_friday => Some(Weekday::Fri),
+        Rule::dt_saturday => Some(Weekday::Sat),
+        Rule::dt_sunday => Some(Weekday::Sun),
+        _ => {
+            tracing::trace!("Unexpected rule in datetime parsing: {:?}", rule);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    fn datetime(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f").expect("datetime must be valid for this test")
+    }
+
+    fn offset(hours: i32, minutes: i32) -> Option<FixedOffset> {
+        FixedOffset::east_opt(hours * 3600 + hours.signum() * minutes * 60)
+    }
+
+    #[rstest]
+    #[case::rfc3339_utc("2024-10-18T12:00:01Z", "2024-10-18 12:00:01", offset(0, 0), DateTimeFormat::Rfc3339)]
+    #[case::rfc3339_fraction_offset("2024-10-18t12:00:01.123+02:00", "2024-10-18 12:00:01.123", offset(2, 0), DateTimeFormat::Rfc3339)]
+    #[case::rfc3339_space("2024-10-18 12:00:01-05:30", "2024-10-18 12:00:01", offset(-5, 30), DateTimeFormat::Rfc3339)]
+    #[case::iso_extended_date("2024-10-18", "2024-10-18 00:00:00", None, DateTimeFormat::Iso8601Extended)]
+    #[case::iso_extended_minutes("2024-10-18T12:00", "2024-10-18 12:00:00", None, DateTimeFormat::Iso8601Extended)]
+    #[case::iso_extended_no_offset("2024-10-18T12:00:01,5", "2024-10-18 12:00:01.5", None, DateTimeFormat::Iso8601Extended)]
+    #[case::iso_extended_basic_offset("2024-10-18T12:00:01+0200", "2024-10-18 12:00:01", offset(2, 0), DateTimeFormat::Iso8601Extended)]
+    #[case::iso_extended_hour_offset("2024-10-18T12+02", "2024-10-18 12:00:00", offset(2, 0), DateTimeFormat::Iso8601Extended)]
+    #[case::iso_basic_date("20241018", "2024-10-18 00:00:00", None, DateTimeFormat::Iso8601Basic)]
+    #[case::iso_basic_utc("20241018T120001Z", "2024-10-18 12:00:01", offset(0, 0), DateTimeFormat::Iso8601Basic)]
+    #[case::iso_basic_offset("20241018T1200-0700", "2024-10-18 12:00:00", offset(-7, 0), DateTimeFormat::Iso8601Basic)]
+    #[case::rfc2822("Fri, 18 Oct 2024 12:00:01 +0000", "2024-10-18 12:00:01", offset(0, 0), DateTimeFormat::Rfc2822)]
+    #[case::rfc2822_no_weekday("8 Oct 2024 12:00 -0430", "2024-10-08 12:00:00", offset(-4, 30), DateTimeFormat::Rfc2822)]
+    #[case::rfc2822_zone_name("Tue, 1 Oct 2024 09:30:00 PDT", "2024-10-01 09:30:00", offset(-7, 0), DateTimeFormat::Rfc2822)]
+    #[case::rfc2822_military_zone("Tue, 1 Oct 2024 09:30:00 A", "2024-10-01 09:30:00", offset(0, 0), DateTimeFormat::Rfc2822)]
+    #[case::syslog("Oct 18 12:00:01", "2023-10-18 12:00:01", None, DateTimeFormat::Syslog)]
+    #[case::syslog_padded_day("Feb  3 04:05:06.789", "2023-02-03 04:05:06.789", None, DateTimeFormat::Syslog)]
+    #[case::common_log("[18/Oct/2024:12:00:01 +0000]", "2024-10-18 12:00:01", offset(0, 0), DateTimeFormat::CommonLog)]
+    #[case::common_log_unbracketed("18/Oct/2024:12:00:01 -0800", "2024-10-18 12:00:01", offset(-8, 0), DateTimeFormat::CommonLog)]
+    #[case::plain_minutes("2024-10-18 12:00", "2024-10-18 12:00:00", None, DateTimeFormat::Plain)]
+    #[case::plain_seconds("2024-10-18 12:00:01", "2024-10-18 12:00:01", None, DateTimeFormat::Plain)]
+    #[case::plain_slashes("2024/1/8 9:05:00.25", "2024-01-08 09:05:00.25", None, DateTimeFormat::Plain)]
+    #[case::surrounding_whitespace("  2024-10-18T12:00:01Z \n", "2024-10-18 12:00:01", offset(0, 0), DateTimeFormat::Rfc3339)]
+    fn test_parse_datetime(#[case] input: &str, #[case] expected: &str, #[case] expected_offset: Option<FixedOffset>, #[case] expected_format: DateTimeFormat) {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .try_init()
+            .ok();
+
+        let result = parse_datetime_with_year(input, 2023, default_abbreviations());
+        match result {
+            Ok(result) => {
+                assert_eq!(result.datetime, datetime(expected));
+                assert_eq!(result.offset, expected_offset);
+                assert_eq!(result.format, expected_format);
+            }
+            Err(e) => panic!("Expected Ok, got Err: {:?}", e),
+        }
+    }
+
+    #[rstest]
+    #[case::new_years_eve("2023-12-31T23:59:59+00:00", "2023-10-18 12:00:01")]
+    #[case::new_year("2024-01-01T00:00:00+00:00", "2024-10-18 12:00:01")]
+    fn test_parse_datetime_with_clock(#[case] now: &str, #[case] expected: &str) {
+        let clock = crate::FixedClock(DateTime::parse_from_rfc3339(now).expect("clock time must be valid for this test"));
+        match parse_datetime_with_clock("Oct 18 12:00:01", clock) {
+            Ok(result) => assert_eq!(result.datetime, datetime(expected)),
+            Err(e) => panic!("Expected Ok, got Err: {:?}", e),
+        }
+    }
+
+    #[rstest]
+    #[case::invalid_month("2024-13-01T00:00:00Z")]
+    #[case::invalid_day("2023-02-29 12:00")]
+    #[case::invalid_hour("2024-10-18T24:00:00Z")]
+    #[case::invalid_offset_minutes("2024-10-18T12:00:00+02:60")]
+    #[case::mismatched_weekday("Mon, 18 Oct 2024 12:00:01 +0000")]
+    #[case::unbalanced_bracket("[18/Oct/2024:12:00:01 +0000")]
+    #[case::duration("1h30m")]
+    #[case::trailing_text("2024-10-18 12:00 tomorrow")]
+    #[case::empty("")]
+    fn test_parse_datetime_invalid(#[case] input: &str) {
+        let result = parse_datetime(input);
+        match result {
+            Ok(actual) => panic!("Expected Err, got Ok {:?}", actual),
+            Err(e) => assert_eq!(e.to_string(), Error::invalid_time(input).to_string()),
+        }
+    }
+
+    #[rstest]
+    #[case::with_offset("2024-10-18T12:00:01+02:00", Some("2024-10-18T10:00:01+00:00"))]
+    #[case::without_offset("2024-10-18 12:00:01", None)]
+    fn test_parsed_datetime_with_offset(#[case] input: &str, #[case] expected_utc: Option<&str>) {
+        let result = parse_datetime(input).expect("datetime must be valid for this test");
+        let expected = expected_utc.map(|value| DateTime::parse_from_rfc3339(value).expect("expected must be valid"));
+        assert_eq!(result.with_offset(), expected);
+        let fallback = result.and_offset(FixedOffset::east_opt(0).expect("offset must be valid"));
+        assert_eq!(fallback.naive_local(), result.datetime);
+    }
+
+    #[rstest]
+    #[case::default_table(TimeZoneAbbreviations::default(), "Tue, 1 Oct 2024 09:30:00 CEST", offset(2, 0))]
+    #[case::custom_offset(custom_abbreviations(), "Tue, 1 Oct 2024 09:30:00 IST", offset(1, 0))]
+    #[case::custom_named_zone(custom_abbreviations(), "Tue, 1 Oct 2024 09:30:00 ET", offset(-4, 0))]
+    #[case::custom_named_zone_winter(custom_abbreviations(), "Tue, 3 Dec 2024 09:30:00 ET", offset(-5, 0))]
+    #[case::removed(custom_abbreviations(), "Tue, 1 Oct 2024 09:30:00 PDT", None)]
+    #[case::empty_table(TimeZoneAbbreviations::empty(), "Tue, 1 Oct 2024 09:30:00 GMT", None)]
+    fn test_parse_datetime_with_abbreviations(#[case] abbreviations: TimeZoneAbbreviations, #[case] input: &str, #[case] expected: Option<FixedOffset>) {
+        match parse_datetime_with_abbreviations(input, &abbreviations) {
+            Ok(actual) => assert_eq!(actual.offset, expected),
+            Err(e) => {
+                assert_eq!(expected, None);
+                assert_eq!(e.to_string(), Error::invalid_time(input).to_string());
+            }
+        }
+    }
+
+    fn custom_abbreviations() -> TimeZoneAbbreviations {
+        let mut abbreviations = TimeZoneAbbreviations::default();
+        abbreviations.insert("IST", crate::Zone::Offset(FixedOffset::east_opt(3600).expect("offset must be valid")));
+        abbreviations.insert("ET", crate::Zone::Named(chrono_tz::America::New_York));
+        abbreviations.remove("PDT");
+        abbreviations
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").expect("date must be valid for this test")
+    }
+
+    #[rstest]
+    #[case::extended("2024-W05-3", "2024-01-31", IsoDateStyle::Extended)]
+    #[case::basic("2024W053", "2024-01-31", IsoDateStyle::Basic)]
+    #[case::lowercase("2024-w05-3", "2024-01-31", IsoDateStyle::Extended)]
+    #[case::week_only("2024-W05", "2024-01-29", IsoDateStyle::Extended)]
+    #[case::week_only_basic("2024W05", "2024-01-29", IsoDateStyle::Basic)]
+    #[case::first_week_starts_in_previous_year("2025-W01-1", "2024-12-30", IsoDateStyle::Extended)]
+    #[case::last_week_ends_in_next_year("2020-W53-7", "2021-01-03", IsoDateStyle::Extended)]
+    fn test_parse_week_date(#[case] input: &str, #[case] expected: &str, #[case] style: IsoDateStyle) {
+        let expected = date(expected);
+        match parse_week_date(input) {
+            Ok(actual) => assert_eq!(actual, expected),
+            Err(e) => panic!("Expected Ok, got Err: {:?}", e),
+        }
+        let formatted = week_date_as_string(&expected, style);
+        assert_eq!(parse_week_date(&formatted).ok(), Some(expected));
+    }
+
+    #[rstest]
+    #[case::week_zero("2024-W00-1")]
+    #[case::week_53_in_short_year("2024-W53-1")]
+    #[case::day_zero("2024-W05-0")]
+    #[case::day_eight("2024-W05-8")]
+    #[case::mixed_separators("2024-W053")]
+    #[case::single_digit_week("2024-W5")]
+    #[case::calendar_date("2024-01-31")]
+    fn test_parse_week_date_invalid(#[case] input: &str) {
+        match parse_week_date(input) {
+            Ok(actual) => panic!("Expected Err, got Ok {:?}", actual),
+            Err(e) => assert_eq!(e.to_string(), Error::invalid_time(input).to_string()),
+        }
+    }
+
+    #[rstest]
+    #[case::extended("2024-123", "2024-05-02")]
+    #[case::basic("2024123", "2024-05-02")]
+    #[case::first_day("2024-001", "2024-01-01")]
+    #[case::leap_day("2024-366", "2024-12-31")]
+    fn test_parse_ordinal_date(#[case] input: &str, #[case] expected: &str) {
+        let expected = date(expected);
+        match parse_ordinal_date(input) {
+            Ok(actual) => assert_eq!(actual, expected),
+            Err(e) => panic!("Expected Ok, got Err: {:?}", e),
+        }
+    }
+
+    #[rstest]
+    #[case::day_zero("2024-000")]
+    #[case::not_leap_year("2023-366")]
+    #[case::too_large("2024-367")]
+    #[case::two_digits("2024-12")]
+    #[case::calendar_date("20240502")]
+    fn test_parse_ordinal_date_invalid(#[case] input: &str) {
+        match parse_ordinal_date(input) {
+            Ok(actual) => panic!("Expected Err, got Ok {:?}", actual),
+            Err(e) => assert_eq!(e.to_string(), Error::invalid_time(input).to_string()),
+        }
+    }
+
+    #[rstest]
+    #[case::mid_year("2024-01-31", "2024-W05-3", "2024W053", "2024-031", "2024031")]
+    #[case::iso_year_differs("2024-12-30", "2025-W01-1", "2025W011", "2024-365", "2024365")]
+    #[case::leap_day("2024-12-31", "2025-W01-2", "2025W012", "2024-366", "2024366")]
+    fn test_iso_date_as_string(#[case] value: &str, #[case] week_extended: &str, #[case] week_basic: &str, #[case] ordinal_extended: &str, #[case] ordinal_basic: &str) {
+        let value = date(value);
+        assert_eq!(week_date_as_string(&value, IsoDateStyle::Extended), week_extended);
+        assert_eq!(week_date_as_string(&value, IsoDateStyle::Basic), week_basic);
+        assert_eq!(ordinal_date_as_string(&value, IsoDateStyle::Extended), ordinal_extended);
+        assert_eq!(ordinal_date_as_string(&value, IsoDateStyle::Basic), ordinal_basic);
+        assert_eq!(parse_ordinal_date(ordinal_extended).ok(), Some(value));
+    }
+}
@@ -0,0 +1,249 @@
+use std::fmt;
+use std::ops::Add;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use pest::Parser;
+use pest_derive::Parser;
+
+use crate::durations::duration_as_string;
+use crate::{Error, Result};
+
+#[derive(Parser)]
+#[grammar = "grammars/intervals.pest"]
+#[grammar = "grammars/deadlines.pest"]
+struct DeadlineParser;
+
+/// A point on the monotonic clock after which an operation times out.
+///
+/// Arithmetic that would overflow [`Instant`] saturates to a deadline that never expires.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Deadline {
+    expiry: Expiry,
+}
+
+/// `Never` sorts after every instant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Expiry {
+    At(Instant),
+    Never,
+}
+
+impl Deadline {
+    pub fn at(instant: Instant) -> Self {
+        Self { expiry: Expiry::At(instant) }
+    }
+
+    /// A deadline `timeout` from now.
+    pub fn after(timeout: Duration) -> Self {
+        Self::at(Instant::now()) + timeout
+    }
+
+    pub fn never() -> Self {
+        Self { expiry: Expiry::Never }
+    }
+
+    /// The instant of the deadline, or `None` when it never expires.
+    pub fn instant(&self) -> Option<Instant> {
+        match self.expiry {
+            Expiry::At(instant) => Some(instant),
+            Expiry::Never => None,
+        }
+    }
+
+    pub fn is_never(&self) -> bool {
+        self.expiry == Expiry::Never
+    }
+
+    /// The time left until the deadline; zero once expired and [`Duration::MAX`] when it never expires.
+    pub fn remaining(&self) -> Duration {
+        self.remaining_since(Instant::now())
+    }
+
+    pub fn remaining_since(&self, now: Instant) -> Duration {
+        match self.expiry {
+            Expiry::At(instant) => instant.saturating_duration_since(now),
+            Expiry::Never => Duration::MAX,
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(Instant::now())
+    }
+
+    pub fn is_expired_at(&self, now: Instant) -> bool {
+        self.instant()
+            .is_some_and(|instant| instant <= now)
+    }
+
+    /// Extends the deadline, or `None` when the result cannot be represented.  A deadline that never expires stays so.
+    pub fn checked_add(&self, duration: Duration) -> Option<Self> {
+        match self.expiry {
+            Expiry::At(instant) => instant.checked_add(duration).map(Self::at),
+            Expiry::Never => Some(*self),
+        }
+    }
+
+    /// Brings the deadline forward, or `None` when the result cannot be represented.  A deadline that never expires
+    /// stays so.
+    pub fn checked_sub(&self, duration: Duration) -> Option<Self> {
+        match self.expiry {
+            Expiry::At(instant) => instant.checked_sub(duration).map(Self::at),
+            Expiry::Never => Some(*self),
+        }
+    }
+
+    /// Extends the deadline, never expiring when the result cannot be represented.
+    pub fn saturating_add(&self, duration: Duration) -> Self {
+        self.checked_add(duration)
+            .unwrap_or_else(Self::never)
+    }
+}
+
+impl Add<Duration> for Deadline {
+    type Output = Self;
+
+    fn add(self, duration: Duration) -> Self {
+        self.saturating_add(duration)
+    }
+}
+
+impl FromStr for Deadline {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        parse_deadline(value)
+    }
+}
+
+/// Formats the remaining time, `0s` once expired and `never` when it never expires.
+impl fmt::Display for Deadline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_never() {
+            return f.write_str("never");
+        }
+        match self.remaining() {
+            Duration::ZERO => f.write_str("0s"),
+            remaining => f.write_str(&duration_as_string(&remaining)),
+        }
+    }
+}
+
+/// Parses a timeout into a deadline on the monotonic clock.
+///
+/// Accepts anything [`crate::parse_duration`] accepts as a whole input (`5m`, `1h30m`, `00:05:00`), and `now` with an
+/// optional offset (`now`, `now+5m`, `now + 1h30m`).
+#[inline(always)]
+pub fn parse_deadline(input: impl AsRef<str>) -> Result<Deadline> {
+    parse_deadline_from(input, Instant::now())
+}
+
+/// Parses a timeout into a deadline relative to `now`.
+#[inline(always)]
+pub fn parse_deadline_from(input: impl AsRef<str>, now: Instant) -> Result<Deadline> {
+    let input = input.as_ref();
+    let parse_result = DeadlineParser::parse(Rule::deadline, input.trim()).map_err(|_e| Error::invalid_duration(input))?;
+
+    let mut timeout = Duration::ZERO;
+    for pair in parse_result.flat_map(|pair| pair.into_inner()) {
+        match pair.as_rule() {
+            Rule::duration => timeout = crate::durations::parse_duration(pair.as_str())?,
+            Rule::deadline_offset => timeout = crate::durations::parse_duration(pair.into_inner().as_str())?,
+            Rule::deadline_now | Rule::EOI => {}
+            _ => unreachable!("Unexpected rule in deadline parsing: {:?}", pair.as_rule()),
+        }
+    }
+
+    tracing::trace!("Deadline in {:?}: {}", timeout, input);
+    Ok(Deadline::at(now) + timeout)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::shorthand("5m", Duration::from_secs(300))]
+    #[case::compound("1h30m", Duration::from_secs(5400))]
+    #[case::hms("00:05:00", Duration::from_secs(300))]
+    #[case::now("now", Duration::ZERO)]
+    #[case::now_plus("now+5m", Duration::from_secs(300))]
+    #[case::now_plus_spaced("now + 1h30m", Duration::from_secs(5400))]
+    #[case::now_uppercase("NOW+250ms", Duration::from_millis(250))]
+    #[case::whitespace(" 10s ", Duration::from_secs(10))]
+    fn test_parse_deadline(#[case] input: &str, #[case] expected: Duration) {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .try_init()
+            .ok();
+
+        let now = Instant::now();
+        match parse_deadline_from(input, now) {
+            Ok(actual) => assert_eq!(actual.remaining_since(now), expected),
+            Err(e) => panic!("Expected Ok, got Err: {:?}", e),
+        }
+    }
+
+    #[rstest]
+    #[case::empty("")]
+    #[case::text("soon")]
+    #[case::trailing_text("5m from now")]
+    #[case::now_minus("now-5m")]
+    #[case::missing_offset("now+")]
+    fn test_parse_deadline_invalid(#[case] input: &str) {
+        match parse_deadline(input) {
+            Ok(actual) => panic!("Expected Err, got Ok {:?}", actual),
+            Err(e) => assert_eq!(e.to_string(), Error::invalid_duration(input).to_string()),
+        }
+    }
+
+    #[test]
+    fn test_expiry() {
+        let now = Instant::now();
+        let deadline = Deadline::at(now) + Duration::from_secs(5);
+
+        assert!(!deadline.is_expired_at(now));
+        assert!(deadline.is_expired_at(now + Duration::from_secs(5)));
+        assert_eq!(deadline.remaining_since(now + Duration::from_secs(2)), Duration::from_secs(3));
+        assert_eq!(deadline.remaining_since(now + Duration::from_secs(9)), Duration::ZERO);
+        assert!(!Deadline::never().is_expired());
+        assert_eq!(Deadline::never().remaining(), Duration::MAX);
+    }
+
+    #[test]
+    fn test_saturation() {
+        let now = Instant::now();
+        let deadline = Deadline::at(now);
+
+        assert_eq!(deadline.checked_add(Duration::MAX), None);
+        assert!(deadline.saturating_add(Duration::MAX).is_never());
+        assert!((deadline + Duration::MAX).is_never());
+        assert!(parse_deadline_from("now+18446744073709551615s", now).is_ok_and(|deadline| deadline.is_never()));
+        assert_eq!(deadline.checked_sub(Duration::MAX), None);
+        assert_eq!(Deadline::never().checked_add(Duration::from_secs(1)), Some(Deadline::never()));
+        assert_eq!(Deadline::never().checked_sub(Duration::from_secs(1)), Some(Deadline::never()));
+        assert_eq!(deadline.checked_add(Duration::from_secs(1)), Some(Deadline::at(now + Duration::from_secs(1))));
+    }
+
+    #[test]
+    fn test_ordering() {
+        let now = Instant::now();
+        let soon = Deadline::at(now + Duration::from_secs(1));
+        let later = Deadline::at(now + Duration::from_secs(60));
+
+        assert!(soon < later);
+        assert!(later < Deadline::never());
+        assert_eq!(Deadline::never().min(later).min(soon), soon);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Deadline::never().to_string(), "never");
+        assert_eq!(Deadline::at(Instant::now()).to_string(), "0s");
+
+        let formatted = Deadline::after(Duration::from_secs(3600)).to_string();
+        assert!(formatted.starts_with("59m") || formatted == "1h", "unexpected remaining time: {}", formatted);
+    }
+}
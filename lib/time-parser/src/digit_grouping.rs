@@ -0,0 +1,209 @@
+use crate::{Error, Result};
+
+/// Which separators may group the digits of a quantity in threes, such as `1,800,000s` or `1 800 000 ms`.
+///
+/// The first group may be one to three digits wide and every later group exactly three, so `1,5s` is never read as
+/// grouping.  With `,` or `'` enabled, such input is rejected as ambiguous instead of being
+/// split into a list by [`crate::DurationParser::parse_all`].  `_` keeps the grammar's own rule, which allows a wider
+/// first group such as `1000_000`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DigitGrouping {
+    /// Any of `_`, `,`, `'` and ` `.
+    pub separators: Vec<char>,
+}
+
+impl DigitGrouping {
+    /// The separators a policy may contain.
+    pub const SEPARATORS: [char; 4] = ['_', ',', '\'', ' '];
+
+    /// Accepts `separators`, ignoring any not in [`DigitGrouping::SEPARATORS`].
+    pub fn new(separators: impl IntoIterator<Item = char>) -> Self {
+        Self {
+            separators: separators
+                .into_iter()
+                .filter(|separator| Self::SEPARATORS.contains(separator))
+                .collect(),
+        }
+    }
+
+    /// Digits may not be grouped.
+    pub fn none() -> Self {
+        Self { separators: Vec::new() }
+    }
+
+    /// The conventional separator for a BCP 47 language tag, such as `en-US` or `de_CH`, in addition to `_`.
+    ///
+    /// Locales that group with a period (`de`, `es`, `it`, ...) get a space instead, since a period starts a fraction.
+    pub fn for_locale(locale: impl AsRef<str>) -> Self {
+        let locale = locale
+            .as_ref()
+            .replace('_', "-")
+            .to_ascii_lowercase();
+        let mut subtags = locale.split('-');
+        let language = subtags.next().unwrap_or_default();
+        let swiss = subtags.any(|subtag| subtag == "ch" || subtag == "li");
+
+        let separator = match language {
+            "de" | "fr" | "it" | "rm" if swiss => '\'',
+            "en" | "ja" | "ko" | "zh" | "he" | "th" | "hi" => ',',
+            _ => ' ',
+        };
+        Self::new(['_', separator])
+    }
+
+    pub fn allows(&self, separator: char) -> bool {
+        self.separators.contains(&separator)
+    }
+
+    /// Rewrites separators between well-formed groups to `_`, the only one the grammar knows.  Separators are ASCII,
+    /// so byte offsets into `text` stay valid.
+    pub(crate) fn normalize(&self, text: &str, interval: &str) -> Result<String> {
+        let bytes = text.as_bytes();
+        let is_digit = |index: usize| bytes.get(index).is_some_and(u8::is_ascii_digit);
+
+        let mut grouped = Vec::new();
+        let mut index = 0;
+        while index < bytes.len() {
+            if !is_digit(index) || (index > 0 && is_digit(index - 1)) {
+                index += 1;
+                continue;
+            }
+            let start = index;
+            while is_digit(index) {
+                index += 1;
+            }
+            let leading_group = index - start <= 3;
+            let mut separators = Vec::new();
+            while leading_group
+                && bytes
+                    .get(index)
+                    .is_some_and(|separator| self.allows(char::from(*separator)))
+                && (1..=3).all(|offset| is_digit(index + offset))
+                && !is_digit(index + 4)
+            {
+                separators.push(index);
+                index += 4;
+            }
+
+            let ambiguous = bytes
+                .get(index)
+                .is_some_and(|separator| matches!(separator, b',' | b'\'') && self.allows(char::from(*separator)))
+                && is_digit(index + 1);
+            if ambiguous {
+                tracing::trace!("Ambiguous digit grouping at {}: {}", index, interval);
+                return Err(Error::error(format!("digit groups must be three digits wide: {}", interval)));
+            }
+            grouped.extend(separators);
+        }
+
+        let mut bytes = bytes.to_vec();
+        for separator in grouped {
+            bytes[separator] = b'_';
+        }
+        String::from_utf8(bytes).map_err(|_e| Error::invalid_duration(interval))
+    }
+}
+
+/// Allows `_` only.
+impl Default for DigitGrouping {
+    fn default() -> Self {
+        Self::new(['_'])
+    }
+}
+
+/// `true` is the default policy and `false` is [`DigitGrouping::none`].
+impl From<bool> for DigitGrouping {
+    fn from(enabled: bool) -> Self {
+        if enabled {
+            Self::default()
+        } else {
+            Self::none()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rstest::rstest;
+
+    use super::*;
+    use crate::DurationParser;
+
+    #[rstest]
+    #[case::underscore(DigitGrouping::default(), "1_800_000s", Duration::from_secs(1_800_000))]
+    #[case::comma(DigitGrouping::new([',']), "1,800,000s", Duration::from_secs(1_800_000))]
+    #[case::space(DigitGrouping::new([' ']), "1 800 000 ms", Duration::from_millis(1_800_000))]
+    #[case::apostrophe(DigitGrouping::new(['\'']), "1'000ms", Duration::from_secs(1))]
+    #[case::mixed_components(DigitGrouping::new([',']), "1h 2,500ms", Duration::from_millis(3_602_500))]
+    #[case::ungrouped(DigitGrouping::none(), "1800000s", Duration::from_secs(1_800_000))]
+    #[case::long_ungrouped(DigitGrouping::new([',']), "1800000s", Duration::from_secs(1_800_000))]
+    #[case::locale_en(DigitGrouping::for_locale("en-US"), "1,000s", Duration::from_secs(1000))]
+    #[case::locale_swiss(DigitGrouping::for_locale("de_CH"), "1'000s", Duration::from_secs(1000))]
+    #[case::locale_fr(DigitGrouping::for_locale("fr"), "1 000 s", Duration::from_secs(1000))]
+    #[case::locale_underscore(DigitGrouping::for_locale("fr"), "1_000s", Duration::from_secs(1000))]
+    #[case::long_first_underscore_group(DigitGrouping::default(), "1000_000s", Duration::from_secs(1_000_000))]
+    fn test_parse_grouped(#[case] grouping: DigitGrouping, #[case] interval: &str, #[case] expected: Duration) {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .try_init()
+            .ok();
+
+        match DurationParser::new()
+            .digit_grouping(grouping)
+            .parse(interval)
+        {
+            Ok(actual) => assert_eq!(actual, expected),
+            Err(e) => panic!("Expected Ok, got Err: {:?}", e),
+        }
+    }
+
+    #[rstest]
+    #[case::separator_not_allowed(DigitGrouping::default(), "1,800s", "invalid duration")]
+    #[case::underscore_not_allowed(DigitGrouping::new([',']), "1_800s", "invalid duration")]
+    #[case::short_group(DigitGrouping::new([',']), "1,80s", "digit groups must be three digits wide: 1,80s")]
+    #[case::decimal_comma(DigitGrouping::new([',']), "1,5s", "digit groups must be three digits wide: 1,5s")]
+    #[case::long_group(DigitGrouping::new([',']), "1,8000s", "digit groups must be three digits wide: 1,8000s")]
+    #[case::long_first_group(DigitGrouping::new([',']), "1000,000s", "digit groups must be three digits wide: 1000,000s")]
+    #[case::short_last_group(DigitGrouping::new([',']), "1,800,5s", "digit groups must be three digits wide: 1,800,5s")]
+    #[case::short_last_apostrophe_group(DigitGrouping::new(['\'']), "1'800'5s", "digit groups must be three digits wide: 1'800'5s")]
+    #[case::long_underscore_group(DigitGrouping::default(), "1_8000s", "invalid duration")]
+    fn test_parse_grouped_invalid(#[case] grouping: DigitGrouping, #[case] interval: &str, #[case] expected: &str) {
+        match DurationParser::new()
+            .digit_grouping(grouping)
+            .parse(interval)
+        {
+            Ok(actual) => panic!("Expected Err, got Ok {:?}", actual),
+            Err(e) => assert_eq!(e.to_string(), expected),
+        }
+    }
+
+    #[test]
+    fn test_parse_all_lists() {
+        let parser = DurationParser::new().digit_grouping(DigitGrouping::new([',']));
+        assert_eq!(
+            parser.parse_all("1,500ms, 2s,3s").ok(),
+            Some(vec![Duration::from_millis(1500), Duration::from_secs(2), Duration::from_secs(3)])
+        );
+        assert!(parser.parse_all("1,5s").is_err());
+        assert!(parser.parse_all("1,800,5s").is_err());
+        assert_eq!(DurationParser::new().parse_all("1,5s").ok(), Some(vec![Duration::from_secs(5)]));
+    }
+
+    #[test]
+    fn test_default_unit() {
+        let parser = DurationParser::new()
+            .digit_grouping(DigitGrouping::for_locale("en"))
+            .default_unit(crate::Unit::Milliseconds);
+        assert_eq!(parser.parse("1,500").ok(), Some(Duration::from_millis(1500)));
+        assert_eq!(parser.parse("1,500.5").ok(), Some(Duration::from_nanos(1_500_500_000)));
+        assert!(parser.parse("1,5").is_err());
+    }
+
+    #[test]
+    fn test_new_ignores_unknown_separators() {
+        assert_eq!(DigitGrouping::new(['.', ',']).separators, vec![',']);
+        assert_eq!(DigitGrouping::from(false), DigitGrouping::none());
+    }
+}
@@ -3,6 +3,21 @@ pub trait DurationFormatter {
     where
         S: AsRef<str>,
         Self: Sized;
+    /// Converts a parsed [`std::time::Duration`], failing when it does not fit in `Self`.
+    fn from_std_duration(duration: std::time::Duration) -> crate::Result<Self>
+    where
+        Self: Sized;
+    /// Like [`DurationFormatter::parse`], but reads a bare number such as `30` in `unit`.
+    fn parse_with_default_unit<S>(interval: S, unit: crate::Unit) -> crate::Result<Self>
+    where
+        S: AsRef<str>,
+        Self: Sized,
+    {
+        crate::DurationParser::new()
+            .default_unit(unit)
+            .parse(interval)
+            .and_then(Self::from_std_duration)
+    }
     fn to_string(&self) -> String;
 }
 
@@ -15,6 +30,10 @@ impl DurationFormatter for std::time::Duration {
         crate::durations::parse_duration(interval)
     }
 
+    fn from_std_duration(duration: std::time::Duration) -> crate::Result<Self> {
+        Ok(duration)
+    }
+
     fn to_string(&self) -> String {
         crate::durations::duration_as_string(self)
     }
@@ -33,6 +52,13 @@ impl DurationFormatter for chrono::Duration {
         }
     }
 
+    fn from_std_duration(duration: std::time::Duration) -> crate::Result<Self> {
+        Self::from_std(duration).map_err(|_e| {
+            tracing::trace!("Duration out of range for chrono: {:?}", duration);
+            crate::Error::overflow(crate::HumanDuration(duration))
+        })
+    }
+
     fn to_string(&self) -> String {
         let std_duration = chrono_to_std(*self);
         crate::durations::duration_as_string(&std_duration)
@@ -109,4 +135,21 @@ mod tests {
             },
         }
     }
+
+    #[rstest]
+    #[case::seconds("30", crate::Unit::Seconds, StdDuration::from_secs(30))]
+    #[case::milliseconds("30", crate::Unit::Milliseconds, StdDuration::from_millis(30))]
+    #[case::shorthand("2m", crate::Unit::Milliseconds, StdDuration::from_secs(120))]
+    #[case::fraction("1.5", crate::Unit::Seconds, StdDuration::from_millis(1500))]
+    #[case::zero("0", crate::Unit::Hours, StdDuration::ZERO)]
+    fn test_duration_formatter_default_unit(#[case] interval: &str, #[case] unit: crate::Unit, #[case] expected: StdDuration) {
+        assert_eq!(StdDuration::parse_with_default_unit(interval, unit).ok(), Some(expected));
+        assert_eq!(ChronoDuration::parse_with_default_unit(interval, unit).ok(), ChronoDuration::from_std(expected).ok());
+    }
+
+    #[test]
+    fn test_from_std_duration_out_of_range() {
+        assert!(ChronoDuration::from_std_duration(StdDuration::MAX).is_err());
+        assert_eq!(StdDuration::from_std_duration(StdDuration::MAX).ok(), Some(StdDuration::MAX));
+    }
 }
@@ -0,0 +1,802 @@
+use std::time::Duration;
+
+use pest::iterators::{Pair, Pairs};
+use pest::Parser;
+use pest_derive::Parser;
+
+use crate::{DigitGrouping, DurationConstraint, Error, Result, StrictViolation, Unit};
+
+#[derive(Parser)]
+#[grammar = "grammars/intervals.pest"]
+struct DurationGrammar;
+
+/// Settings for a [`DurationParser`].  The defaults match [`crate::parse_duration`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParserOptions {
+    /// Whether units must be lowercase as listed (`1h`), or may use any case (`1H`).
+    pub case_sensitive: bool,
+    /// Whether whitespace may appear inside a duration (`1 h 30 m`).
+    pub whitespace: bool,
+    /// The units that may appear in shorthand.
+    pub units: Vec<Unit>,
+    /// The separators that may group digits in threes (`1_800_000s`, `1,800,000s`).
+    pub digit_grouping: DigitGrouping,
+    /// Whether HMS must be `HH:MM:SS` with two-digit fields.  When off, hours may have any number of digits and
+    /// seconds may be left out (`1:30`, `100:00:00`).
+    pub strict_hms: bool,
+    /// Whether shorthand must be canonical: units strictly descending without repeats, each value below the next
+    /// larger allowed unit (`90m` is rejected when `h` is allowed), and no whitespace other than single spaces.  The
+    /// duration must also make up the whole input.
+    pub strict: bool,
+    /// The unit of a bare number such as `30` or `1.5` making up the whole input.  Without one, bare numbers are
+    /// rejected.  It must be one of `units`.  Numbers inside a larger input (`1h30`) are unaffected.
+    pub default_unit: Option<Unit>,
+    /// Bounds every parsed duration must satisfy.
+    pub constraint: Option<DurationConstraint>,
+    /// Whether a final number without a unit takes the next smaller unit than the one before it, so `1h30` is `1h30m`
+    /// and `2m30` is `2m30s`.  This also allows `1:30h` for `1h30m`.  Only applies when it makes up the whole input.
+    pub implicit_unit: bool,
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        Self {
+            case_sensitive: true,
+            whitespace: true,
+            units: Unit::ALL.to_vec(),
+            digit_grouping: DigitGrouping::default(),
+            strict_hms: true,
+            strict: false,
+            default_unit: None,
+            constraint: None,
+            implicit_unit: false,
+        }
+    }
+}
+
+/// A duration parser configured with [`ParserOptions`].
+///
+/// ```
+/// use std::time::Duration;
+/// use time_parser::{DurationParser, Unit};
+///
+/// let parser = DurationParser::new()
+///     .case_sensitive(false)
+///     .units([Unit::Hours, Unit::Minutes]);
+/// assert_eq!(parser.parse("1H30M").ok(), Some(Duration::from_secs(5400)));
+/// assert!(parser.parse("30s").is_err());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DurationParser {
+    options: ParserOptions,
+}
+
+impl DurationParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_options(options: ParserOptions) -> Self {
+        Self { options }
+    }
+
+    pub fn options(&self) -> &ParserOptions {
+        &self.options
+    }
+
+    pub fn case_sensitive(mut self, case_sensitive: bool) -> Self {
+        self.options.case_sensitive = case_sensitive;
+        self
+    }
+
+    pub fn whitespace(mut self, whitespace: bool) -> Self {
+        self.options.whitespace = whitespace;
+        self
+    }
+
+    pub fn units(mut self, units: impl IntoIterator<Item = Unit>) -> Self {
+        self.options.units = units.into_iter().collect();
+        self
+    }
+
+    pub fn digit_grouping(mut self, digit_grouping: impl Into<DigitGrouping>) -> Self {
+        self.options.digit_grouping = digit_grouping.into();
+        self
+    }
+
+    pub fn strict_hms(mut self, strict_hms: bool) -> Self {
+        self.options.strict_hms = strict_hms;
+        self
+    }
+
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.options.strict = strict;
+        self
+    }
+
+    pub fn default_unit(mut self, default_unit: impl Into<Option<Unit>>) -> Self {
+        self.options.default_unit = default_unit.into();
+        self
+    }
+
+    pub fn constraint(mut self, constraint: impl Into<Option<DurationConstraint>>) -> Self {
+        self.options.constraint = constraint.into();
+        self
+    }
+
+    pub fn implicit_unit(mut self, implicit_unit: bool) -> Self {
+        self.options.implicit_unit = implicit_unit;
+        self
+    }
+
+    /// Parses a duration in either shorthand or HMS form, or a bare number when a default unit is set.
+    pub fn parse(&self, interval: impl AsRef<str>) -> Result<Duration> {
+        let interval = interval.as_ref();
+        if let Some(unit) = self.options.default_unit {
+            let normalized = self.normalize(interval)?;
+            let underscores_allowed = self.options.digit_grouping.allows('_') || !interval.contains('_');
+            match DurationGrammar::parse(Rule::duration_bare, &normalized) {
+                Ok(parse_result) if underscores_allowed => {
+                    self.check_bare_number(unit, interval)?;
+                    return parse_bare_number(parse_result, unit, interval).and_then(|duration| self.check(duration, interval));
+                }
+                _ => {}
+            }
+        }
+        if let Some(duration) = self.parse_implicit(interval)? {
+            return Ok(duration);
+        }
+        let rule = if self.options.strict_hms { Rule::duration } else { Rule::duration_lenient };
+        self.parse_one(rule, interval)
+    }
+
+    /// Parses a duration in HMS form only.
+    pub fn parse_hms(&self, interval: impl AsRef<str>) -> Result<Duration> {
+        let rule = if self.options.strict_hms { Rule::duration_hms } else { Rule::duration_hms_lenient };
+        self.parse_one(rule, interval.as_ref())
+    }
+
+    /// Parses a duration in shorthand form only.
+    pub fn parse_shorthand(&self, interval: impl AsRef<str>) -> Result<Duration> {
+        let interval = interval.as_ref();
+        if let Some(duration) = self.parse_implicit(interval)? {
+            return Ok(duration);
+        }
+        self.parse_one(Rule::duration_shorthand, interval)
+    }
+
+    /// Finds every duration in free text, such as `2d and 3h`.
+    pub fn parse_all(&self, string: impl AsRef<str>) -> Result<Vec<Duration>> {
+        let string = string.as_ref();
+        let rule = if self.options.strict_hms { Rule::durations } else { Rule::durations_lenient };
+        let normalized = self.normalize(string)?;
+        let parse_result = DurationGrammar::parse(rule, &normalized).map_err(|_e| Error::invalid_duration(string))?;
+
+        let durations = parse_result
+            .map(|pair| {
+                self.evaluate(pair, string, None)
+                    .and_then(|duration| self.check(duration, string))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if durations.is_empty() {
+            tracing::trace!("No valid duration values found in input: {}", string);
+            return Err(Error::invalid_duration(string));
+        }
+        Ok(durations)
+    }
+
+    /// Parses a shorthand or HMS quantity that may include business days, returning them apart from the rest.
+    pub(crate) fn parse_business(&self, interval: impl AsRef<str>) -> Result<(u64, Duration)> {
+        let interval = interval.as_ref();
+        let normalized = self.normalize(interval)?;
+        let parse_result = DurationGrammar::parse(Rule::business_duration, &normalized).map_err(|_e| Error::invalid_duration(interval))?;
+
+        let mut business_days = 0;
+        let mut total = Duration::ZERO;
+        for pair in parse_result.flat_map(|pair| pair.into_inner()) {
+            if pair.as_rule() == Rule::EOI {
+                continue;
+            }
+            let duration = self.evaluate(pair, interval, Some(&mut business_days))?;
+            total = checked_add(total, duration, interval)?;
+        }
+        Ok((business_days, total))
+    }
+
+    #[inline(always)]
+    fn parse_one(&self, rule: Rule, interval: &str) -> Result<Duration> {
+        let normalized = self.normalize(interval)?;
+        let parse_result = DurationGrammar::parse(rule, &normalized).map_err(|_e| Error::invalid_duration(interval))?;
+
+        let Some(pair) = parse_result.into_iter().next() else {
+            tracing::trace!("No valid duration values found in input: {}", interval);
+            return Err(Error::invalid_duration(interval));
+        };
+        // The span of a shorthand can take in whitespace after its last unit, so measure to the last token instead.
+        let end = pair
+            .clone()
+            .into_inner()
+            .flatten()
+            .last()
+            .map_or(pair.as_span().end(), |token| token.as_span().end());
+        if self.options.strict && end < interval.len() {
+            tracing::trace!("Trailing input after {}: {}", end, interval);
+            return Err(Error::strict(StrictViolation::TrailingInput(interval[end..].to_string()), interval));
+        }
+        self.evaluate(pair, interval, None)
+            .and_then(|duration| self.check(duration, interval))
+    }
+
+    /// Parses `1h30` or `1:30h` when implicit units are enabled, or returns `None` so other forms can be tried.
+    #[inline(always)]
+    fn parse_implicit(&self, interval: &str) -> Result<Option<Duration>> {
+        if !self.options.implicit_unit {
+            return Ok(None);
+        }
+        let normalized = self.normalize(interval)?;
+        let Ok(parse_result) = DurationGrammar::parse(Rule::duration_implicit, &normalized) else {
+            return Ok(None);
+        };
+        let Some(form) = parse_result
+            .flat_map(|pair| pair.into_inner())
+            .next()
+        else {
+            unreachable!("Implicit duration must have a form");
+        };
+        self.check_whitespace(form.as_str(), interval)?;
+
+        // The trailing number of `1:30h` is bounded like an HMS field; that of `1h30` only in strict mode.
+        let (larger_unit, total, trailing_digits, bounded) = match form.as_rule() {
+            Rule::duration_trailing => {
+                let mut inner_pairs = form.into_inner();
+                let (Some(shorthand), Some(trailing_digits)) = (inner_pairs.next(), inner_pairs.next()) else {
+                    unreachable!("Trailing duration must have shorthand and digits");
+                };
+                let Some(unit_pair) = shorthand.clone().into_inner().last() else {
+                    unreachable!("Shorthand must end with a unit");
+                };
+                let larger_unit = unit_pair.as_str().parse::<Unit>().map_err(|_e| {
+                    tracing::trace!("Unit without a fixed length before trailing number: {}", unit_pair.as_str());
+                    Error::invalid_duration(interval)
+                })?;
+                (larger_unit, self.evaluate_shorthand(shorthand, interval, None)?, trailing_digits, false)
+            }
+            Rule::duration_colon_unit => {
+                let mut inner_pairs = form.into_inner();
+                let (Some(digits_pair), Some(trailing_digits), Some(unit_pair)) = (inner_pairs.next(), inner_pairs.next(), inner_pairs.next()) else {
+                    unreachable!("Colon duration must have two numbers and a unit");
+                };
+                let larger_unit = self.allowed_unit(&unit_pair, interval)?;
+                let value = self.quantity(&digits_pair, interval)?;
+                if self.options.strict {
+                    self.check_canonical(value, larger_unit, None, interval)?;
+                }
+                (larger_unit, checked_duration(larger_unit, value, interval)?, trailing_digits, true)
+            }
+            _ => unreachable!("Unexpected rule in implicit duration parsing: {:?}", form.as_rule()),
+        };
+
+        let Some(unit) = larger_unit.smaller() else {
+            tracing::trace!("No unit smaller than {}: {}", larger_unit, interval);
+            return Err(Error::error(format!("no unit smaller than {} for the trailing number: {}", larger_unit, interval)));
+        };
+        if !self.options.units.contains(&unit) {
+            tracing::trace!("Implicit unit not allowed: {}", unit);
+            return Err(Error::invalid_unit(unit));
+        }
+        if let Some(constraint) = &self.options.constraint {
+            constraint.check_unit(unit, interval)?;
+        }
+
+        let value = self.quantity(&trailing_digits, interval)?;
+        let limit = larger_unit.nanoseconds() / unit.nanoseconds();
+        if self.options.strict {
+            self.check_canonical(value, unit, Some(larger_unit), interval)?;
+        } else if bounded && value >= limit {
+            tracing::trace!("Value {}{} must be below {}: {}", value, unit, limit, interval);
+            return Err(Error::invalid_duration(interval));
+        }
+
+        let duration = checked_add(total, checked_duration(unit, value, interval)?, interval)?;
+        self.check(duration, interval).map(Some)
+    }
+
+    /// Applies the constraint, if any, to a parsed duration.
+    #[inline(always)]
+    fn check(&self, duration: Duration, interval: &str) -> Result<Duration> {
+        match &self.options.constraint {
+            Some(constraint) => constraint.check(duration, interval),
+            None => Ok(duration),
+        }
+    }
+
+    /// Lowercases ASCII for case-insensitive parsing and rewrites digit groups to underscores, both of which keep byte
+    /// offsets intact.
+    #[inline(always)]
+    fn normalize(&self, interval: &str) -> Result<String> {
+        let text = if self.options.case_sensitive { interval.to_string() } else { interval.to_ascii_lowercase() };
+        self.options
+            .digit_grouping
+            .normalize(&text, interval)
+    }
+
+    /// Sums a `duration`, HMS or shorthand pair.  Business days are counted into `business_days` when given, in which
+    /// case calendar days and weeks are rejected as they have no working-time length.
+    fn evaluate(&self, pair: Pair<Rule>, interval: &str, business_days: Option<&mut u64>) -> Result<Duration> {
+        self.check_whitespace(pair.as_str(), interval)?;
+
+        match pair.as_rule() {
+            Rule::duration | Rule::duration_lenient => {
+                let Some(form) = pair.into_inner().next() else {
+                    unreachable!("Duration must have a form");
+                };
+                self.evaluate(form, interval, business_days)
+            }
+            Rule::duration_hms | Rule::duration_hms_lenient => {
+                let (mut hours, mut minutes, mut seconds) = (0, 0, 0);
+                for field in pair.into_inner() {
+                    let value = parse_quantity(field.as_str(), interval)?;
+                    match field.as_rule() {
+                        Rule::hms_hour | Rule::hms_lenient_hour => hours = value,
+                        Rule::hms_minute => minutes = value,
+                        Rule::hms_second => seconds = value,
+                        _ => unreachable!("Unexpected rule in hms parsing: {:?}", field.as_rule()),
+                    }
+                }
+                if minutes > 59 || seconds > 59 {
+                    tracing::trace!("Invalid minutes or seconds: {}", interval);
+                    return Err(Error::invalid_duration(interval));
+                }
+                [(hours, Unit::Hours), (minutes, Unit::Minutes), (seconds, Unit::Seconds)]
+                    .into_iter()
+                    .try_fold(Duration::ZERO, |total, (value, unit)| checked_add(total, checked_duration(unit, value, interval)?, interval))
+            }
+            Rule::duration_shorthand | Rule::business_shorthand => self.evaluate_shorthand(pair, interval, business_days),
+            _ => unreachable!("Unexpected rule in duration parsing: {:?}", pair.as_rule()),
+        }
+    }
+
+    #[inline(always)]
+    fn evaluate_shorthand(&self, pair: Pair<Rule>, interval: &str, mut business_days: Option<&mut u64>) -> Result<Duration> {
+        let mut total = Duration::ZERO;
+        let mut previous_unit = None;
+        let mut inner_pairs = pair.into_inner();
+        while let (Some(digits_pair), Some(unit_pair)) = (inner_pairs.next(), inner_pairs.next()) {
+            let value = self.quantity(&digits_pair, interval)?;
+
+            if unit_pair.as_rule() == Rule::units_business_days {
+                let Some(business_days) = business_days.as_deref_mut() else {
+                    tracing::trace!("Business days have no fixed length: {}", interval);
+                    return Err(Error::invalid_duration(interval));
+                };
+                *business_days += value;
+                continue;
+            }
+
+            let unit = self.allowed_unit(&unit_pair, interval)?;
+            if business_days.is_some() && matches!(unit, Unit::Days | Unit::Weeks) {
+                tracing::trace!("Unit {} is not working time: {}", unit, interval);
+                return Err(Error::error(format!("unit {} has no working-time meaning: {}", unit, interval)));
+            }
+            if self.options.strict {
+                self.check_canonical(value, unit, previous_unit, interval)?;
+            }
+            previous_unit = Some(unit);
+            total = checked_add(total, checked_duration(unit, value, interval)?, interval)?;
+        }
+        Ok(total)
+    }
+}
+
+impl DurationParser {
+    /// Parses a shorthand quantity, rejecting `_` when the digit grouping policy does not allow it.
+    #[inline(always)]
+    fn quantity(&self, digits_pair: &Pair<Rule>, interval: &str) -> Result<u64> {
+        let written = &interval[digits_pair.as_span().start()..digits_pair.as_span().end()];
+        if !self.options.digit_grouping.allows('_') && written.contains('_') {
+            tracing::trace!("Digit grouping is disabled: {}", written);
+            return Err(Error::invalid_duration(interval));
+        }
+        parse_quantity(digits_pair.as_str(), interval)
+    }
+
+    /// Checks the default unit of a bare number against the allowed units and the constraint as if it had been
+    /// written, and rejects whitespace around the number in strict mode.
+    #[inline(always)]
+    fn check_bare_number(&self, unit: Unit, interval: &str) -> Result<()> {
+        if !self.options.units.contains(&unit) {
+            tracing::trace!("Default unit not allowed: {}", unit);
+            return Err(Error::invalid_unit(unit.symbol()));
+        }
+        if let Some(constraint) = &self.options.constraint {
+            constraint.check_unit(unit, interval)?;
+        }
+        if self.options.strict && interval.trim() != interval {
+            tracing::trace!("Whitespace around bare number: {:?}", interval);
+            return Err(Error::strict(StrictViolation::Whitespace, interval));
+        }
+        Ok(())
+    }
+
+    /// Parses a shorthand unit and checks it against the allowed units and the constraint.
+    #[inline(always)]
+    fn allowed_unit(&self, unit_pair: &Pair<Rule>, interval: &str) -> Result<Unit> {
+        let unit_text = &interval[unit_pair.as_span().start()..unit_pair.as_span().end()];
+        let unit = unit_pair.as_str().parse::<Unit>()?;
+        if !self.options.units.contains(&unit) {
+            tracing::trace!("Unit not allowed: {}", unit_text);
+            return Err(Error::invalid_unit(unit_text));
+        }
+        if let Some(constraint) = &self.options.constraint {
+            constraint.check_unit(unit, interval)?;
+        }
+        Ok(unit)
+    }
+
+    /// Rejects whitespace the options do not allow.
+    #[inline(always)]
+    fn check_whitespace(&self, text: &str, interval: &str) -> Result<()> {
+        if !self.options.whitespace && text.chars().any(char::is_whitespace) {
+            tracing::trace!("Whitespace inside duration: {}", text);
+            return Err(Error::invalid_duration(interval));
+        }
+        if self.options.strict && !has_tight_whitespace(text) {
+            tracing::trace!("Whitespace other than a single space: {:?}", text);
+            return Err(Error::strict(StrictViolation::Whitespace, interval));
+        }
+        Ok(())
+    }
+
+    /// Checks a shorthand component against the one before it and the allowed units for strict mode.
+    #[inline(always)]
+    fn check_canonical(&self, value: u64, unit: Unit, previous_unit: Option<Unit>, interval: &str) -> Result<()> {
+        if previous_unit == Some(unit) {
+            tracing::trace!("Repeated unit {}: {}", unit, interval);
+            return Err(Error::strict(StrictViolation::RepeatedUnit(unit), interval));
+        }
+        if let Some(previous_unit) = previous_unit.filter(|previous_unit| *previous_unit < unit) {
+            tracing::trace!("Unit {} after {}: {}", unit, previous_unit, interval);
+            return Err(Error::strict(StrictViolation::UnitOrder { unit, previous: previous_unit }, interval));
+        }
+
+        let larger_unit = self
+            .options
+            .units
+            .iter()
+            .filter(|allowed| **allowed > unit)
+            .min();
+        if let Some(larger_unit) = larger_unit {
+            let limit = larger_unit.nanoseconds() / unit.nanoseconds();
+            if value >= limit {
+                tracing::trace!("Value {}{} must be below {}: {}", value, unit, limit, interval);
+                return Err(Error::strict(
+                    StrictViolation::OutOfRange {
+                        value,
+                        unit,
+                        limit,
+                        larger_unit: *larger_unit,
+                    },
+                    interval,
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Converts a whole-input bare number to a duration in `unit`, rejecting fractions finer than a nanosecond.
+#[inline(always)]
+fn parse_bare_number(pairs: Pairs<Rule>, unit: Unit, interval: &str) -> Result<Duration> {
+    let (mut integer, mut fraction) = ("", "");
+    for pair in pairs.flatten() {
+        match pair.as_rule() {
+            Rule::bare_integer => integer = pair.as_str(),
+            Rule::bare_fraction => fraction = pair.as_str(),
+            _ => {}
+        }
+    }
+
+    let fraction = fraction.trim_end_matches('0');
+    let unit_nanoseconds = unit.nanoseconds() as u128;
+    let integer_nanoseconds = integer
+        .replace('_', "")
+        .parse::<u128>()
+        .ok()
+        .and_then(|integer| integer.checked_mul(unit_nanoseconds));
+    let fraction_nanoseconds = match fraction {
+        "" => Some(0),
+        fraction => {
+            let scale = u32::try_from(fraction.len())
+                .ok()
+                .and_then(|digits| 10u128.checked_pow(digits));
+            let scaled = fraction
+                .parse::<u128>()
+                .ok()
+                .and_then(|fraction| fraction.checked_mul(unit_nanoseconds));
+            match (scaled, scale) {
+                (Some(scaled), Some(scale)) if scaled % scale == 0 => Some(scaled / scale),
+                _ => {
+                    tracing::trace!("Fraction finer than a nanosecond: {}", interval);
+                    return Err(Error::invalid_duration(interval));
+                }
+            }
+        }
+    };
+
+    integer_nanoseconds
+        .zip(fraction_nanoseconds)
+        .and_then(|(integer, fraction)| integer.checked_add(fraction))
+        .and_then(|nanoseconds| {
+            let seconds = u64::try_from(nanoseconds / 1_000_000_000).ok()?;
+            Some(Duration::new(seconds, (nanoseconds % 1_000_000_000) as u32))
+        })
+        .ok_or_else(|| {
+            tracing::trace!("Bare number too large: {}", interval);
+            Error::overflow(interval)
+        })
+}
+
+/// Whether all whitespace is single spaces.
+#[inline(always)]
+fn has_tight_whitespace(text: &str) -> bool {
+    !text.contains("  ")
+        && text
+            .chars()
+            .all(|c| c == ' ' || !c.is_whitespace())
+}
+
+#[inline(always)]
+fn parse_quantity(digits: &str, interval: &str) -> Result<u64> {
+    digits
+        .replace('_', "")
+        .parse::<u64>()
+        .map_err(|_e| {
+            tracing::trace!("Invalid quantity: {}", digits);
+            Error::invalid_duration(interval)
+        })
+}
+
+#[inline(always)]
+fn checked_duration(unit: Unit, value: u64, interval: &str) -> Result<Duration> {
+    unit.checked_duration(value).ok_or_else(|| {
+        tracing::trace!("Quantity too large: {}{}", value, unit);
+        Error::overflow(interval)
+    })
+}
+
+#[inline(always)]
+fn checked_add(total: Duration, duration: Duration, interval: &str) -> Result<Duration> {
+    total.checked_add(duration).ok_or_else(|| {
+        tracing::trace!("Duration too large: {}", interval);
+        Error::overflow(interval)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::case_insensitive(DurationParser::new().case_sensitive(false), "1H30M", Duration::from_secs(5400))]
+    #[case::case_insensitive_long(DurationParser::new().case_sensitive(false), "2 Hours", Duration::from_secs(7200))]
+    #[case::allowed_units(DurationParser::new().units([Unit::Hours, Unit::Minutes]), "1h30m", Duration::from_secs(5400))]
+    #[case::no_whitespace(DurationParser::new().whitespace(false), "1h30m", Duration::from_secs(5400))]
+    #[case::no_grouping(DurationParser::new().digit_grouping(false), "1800000s", Duration::from_secs(1_800_000))]
+    #[case::lenient_hms(DurationParser::new().strict_hms(false), "1:30", Duration::from_secs(5400))]
+    #[case::lenient_hms_long_hours(DurationParser::new().strict_hms(false), "100:00:05", Duration::from_secs(360_005))]
+    #[case::lenient_shorthand(DurationParser::new().strict_hms(false), "1h30m", Duration::from_secs(5400))]
+    fn test_parse(#[case] parser: DurationParser, #[case] interval: &str, #[case] expected: Duration) {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .try_init()
+            .ok();
+
+        match parser.parse(interval) {
+            Ok(actual) => assert_eq!(actual, expected),
+            Err(e) => panic!("Expected Ok, got Err: {:?}", e),
+        }
+    }
+
+    #[rstest]
+    #[case::case_sensitive(DurationParser::new(), "1H", Error::invalid_duration("1H"))]
+    #[case::disallowed_unit(DurationParser::new().units([Unit::Hours, Unit::Minutes]), "1h30sec", Error::invalid_unit("sec"))]
+    #[case::disallowed_unit_case_insensitive(DurationParser::new().case_sensitive(false).units([Unit::Hours]), "1H30M", Error::invalid_unit("M"))]
+    #[case::whitespace(DurationParser::new().whitespace(false), "1h 30m", Error::invalid_duration("1h 30m"))]
+    #[case::whitespace_before_unit(DurationParser::new().whitespace(false), "1 h", Error::invalid_duration("1 h"))]
+    #[case::grouping(DurationParser::new().digit_grouping(false), "1_800_000s", Error::invalid_duration("1_800_000s"))]
+    #[case::strict_hms(DurationParser::new(), "1:30", Error::invalid_duration("1:30"))]
+    #[case::lenient_hms_minutes(DurationParser::new().strict_hms(false), "1:75", Error::invalid_duration("1:75"))]
+    #[case::overflow(DurationParser::new(), "18446744073709551615w", Error::overflow("18446744073709551615w"))]
+    #[case::business_days(DurationParser::new(), "3bd", Error::invalid_duration("3bd"))]
+    fn test_parse_invalid(#[case] parser: DurationParser, #[case] interval: &str, #[case] expected: Error) {
+        match parser.parse(interval) {
+            Ok(actual) => panic!("Expected Err, got Ok {:?}", actual),
+            Err(e) => assert_eq!(e.to_string(), expected.to_string()),
+        }
+    }
+
+    #[rstest]
+    #[case::canonical("1h30m", Duration::from_secs(5400))]
+    #[case::single_spaces("1 h 30 m", Duration::from_secs(5400))]
+    #[case::spaced_components("1w 6d 23h", Duration::from_secs(604_800 + 6 * 86400 + 23 * 3600))]
+    #[case::largest_unit_unbounded("100w", Duration::from_secs(100 * 604_800))]
+    #[case::subsecond("1s999ms999us", Duration::from_nanos(1_999_999_000))]
+    #[case::hms("01:30:00", Duration::from_secs(5400))]
+    fn test_parse_strict(#[case] interval: &str, #[case] expected: Duration) {
+        match DurationParser::new().strict(true).parse(interval) {
+            Ok(actual) => assert_eq!(actual, expected),
+            Err(e) => panic!("Expected Ok, got Err: {:?}", e),
+        }
+    }
+
+    #[rstest]
+    #[case::overflowing_days(DurationParser::new(), "1w7d", "7d is not below the 7d in a w: 1w7d")]
+    #[case::out_of_order(DurationParser::new(), "30m1h", "unit h must come before m: 30m1h")]
+    #[case::repeated(DurationParser::new(), "1h 1h", "repeated unit h: 1h 1h")]
+    #[case::repeated_aliases(DurationParser::new(), "1h1hour", "repeated unit h: 1h1hour")]
+    #[case::newline(DurationParser::new(), "1 h\n30 m", "whitespace other than a single space: 1 h\n30 m")]
+    #[case::tab(DurationParser::new(), "1h\t30m", "whitespace other than a single space: 1h\t30m")]
+    #[case::double_space(DurationParser::new(), "1h  30m", "whitespace other than a single space: 1h  30m")]
+    #[case::bounded_minutes(DurationParser::new(), "90m", "90m is not below the 60m in a h: 90m")]
+    #[case::bounded_milliseconds(DurationParser::new(), "1500ms", "1500ms is not below the 1000ms in a s: 1500ms")]
+    #[case::bound_skips_disallowed_units(DurationParser::new().units([Unit::Days, Unit::Minutes]), "1500m", "1500m is not below the 1440m in a d: 1500m")]
+    #[case::trailing_text(DurationParser::new(), "1h 30m junk", "trailing input \" junk\": 1h 30m junk")]
+    #[case::trailing_space(DurationParser::new(), "1h30m ", "trailing input \" \": 1h30m ")]
+    fn test_parse_strict_invalid(#[case] parser: DurationParser, #[case] interval: &str, #[case] expected: &str) {
+        match parser.strict(true).parse(interval) {
+            Ok(actual) => panic!("Expected Err, got Ok {:?}", actual),
+            Err(e) => assert_eq!(e.to_string(), expected),
+        }
+    }
+
+    #[rstest]
+    #[case::repeated("1h 1h", StrictViolation::RepeatedUnit(Unit::Hours))]
+    #[case::out_of_order("30m1h", StrictViolation::UnitOrder { unit: Unit::Hours, previous: Unit::Minutes })]
+    #[case::out_of_range("90m", StrictViolation::OutOfRange { value: 90, unit: Unit::Minutes, limit: 60, larger_unit: Unit::Hours })]
+    #[case::whitespace("1h  30m", StrictViolation::Whitespace)]
+    #[case::trailing_input("1h junk", StrictViolation::TrailingInput(" junk".to_string()))]
+    fn test_parse_strict_violation(#[case] interval: &str, #[case] expected: StrictViolation) {
+        match DurationParser::new().strict(true).parse(interval) {
+            Err(Error::Strict(violation, value)) => {
+                assert_eq!(violation, expected);
+                assert_eq!(value, interval);
+            }
+            other => panic!("Expected a strict violation, got {:?}", other),
+        }
+    }
+
+    #[rstest]
+    #[case::seconds(Unit::Seconds, "30", Duration::from_secs(30))]
+    #[case::milliseconds(Unit::Milliseconds, "30", Duration::from_millis(30))]
+    #[case::decimal(Unit::Seconds, "1.5", Duration::from_millis(1500))]
+    #[case::decimal_minutes(Unit::Minutes, "0.25", Duration::from_secs(15))]
+    #[case::trailing_zeros(Unit::Nanoseconds, "7.000", Duration::from_nanos(7))]
+    #[case::whitespace(Unit::Seconds, " 30 ", Duration::from_secs(30))]
+    #[case::with_unit(Unit::Milliseconds, "30s", Duration::from_secs(30))]
+    #[case::hms(Unit::Milliseconds, "00:00:30", Duration::from_secs(30))]
+    #[case::mixed_number_ignored(Unit::Seconds, "1h30", Duration::from_secs(3600))]
+    fn test_parse_default_unit(#[case] unit: Unit, #[case] interval: &str, #[case] expected: Duration) {
+        match DurationParser::new()
+            .default_unit(unit)
+            .parse(interval)
+        {
+            Ok(actual) => assert_eq!(actual, expected),
+            Err(e) => panic!("Expected Ok, got Err: {:?}", e),
+        }
+    }
+
+    #[rstest]
+    #[case::no_default_unit(None, "30", Error::invalid_duration("30"))]
+    #[case::sub_nanosecond(Some(Unit::Nanoseconds), "1.5", Error::invalid_duration("1.5"))]
+    #[case::trailing_dot(Some(Unit::Seconds), "30.", Error::invalid_duration("30."))]
+    #[case::overflow(Some(Unit::Weeks), "18446744073709551615", Error::overflow("18446744073709551615"))]
+    fn test_parse_default_unit_invalid(#[case] unit: Option<Unit>, #[case] interval: &str, #[case] expected: Error) {
+        match DurationParser::new()
+            .default_unit(unit)
+            .parse(interval)
+        {
+            Ok(actual) => panic!("Expected Err, got Ok {:?}", actual),
+            Err(e) => assert_eq!(e.to_string(), expected.to_string()),
+        }
+    }
+
+    #[rstest]
+    #[case::unit_not_allowed(DurationParser::new().units([Unit::Hours]), "30", "invalid unit: s")]
+    #[case::strict_whitespace(DurationParser::new().strict(true), " 30 ", "whitespace other than a single space:  30 ")]
+    fn test_parse_default_unit_restricted(#[case] parser: DurationParser, #[case] interval: &str, #[case] expected: &str) {
+        match parser.default_unit(Unit::Seconds).parse(interval) {
+            Ok(actual) => panic!("Expected Err, got Ok {:?}", actual),
+            Err(e) => assert_eq!(e.to_string(), expected),
+        }
+    }
+
+    #[rstest]
+    #[case::weeks_days("2w3", Duration::from_secs(17 * 86400))]
+    #[case::days_hours("1d12", Duration::from_secs(36 * 3600))]
+    #[case::hours_minutes("1h30", Duration::from_secs(5400))]
+    #[case::minutes_seconds("2m30", Duration::from_secs(150))]
+    #[case::seconds_milliseconds("1s500", Duration::from_millis(1500))]
+    #[case::milliseconds_microseconds("1ms250", Duration::from_micros(1250))]
+    #[case::microseconds_nanoseconds("1µs5", Duration::from_nanos(1005))]
+    #[case::after_components("1d2h30", Duration::from_secs(86400 + 9000))]
+    #[case::long_name("1 hour 30", Duration::from_secs(5400))]
+    #[case::unbounded("1h90", Duration::from_secs(9000))]
+    #[case::colon_weeks_days("1:3w", Duration::from_secs(10 * 86400))]
+    #[case::colon_days_hours("1:12d", Duration::from_secs(36 * 3600))]
+    #[case::colon_hours_minutes("1:30h", Duration::from_secs(5400))]
+    #[case::colon_minutes_seconds("2:30m", Duration::from_secs(150))]
+    #[case::colon_seconds_milliseconds("1:500s", Duration::from_millis(1500))]
+    #[case::colon_milliseconds_microseconds("1:250ms", Duration::from_micros(1250))]
+    #[case::colon_microseconds_nanoseconds("1:5us", Duration::from_nanos(1005))]
+    #[case::colon_spaced_unit("1:30 hours", Duration::from_secs(5400))]
+    #[case::explicit_units("1h30m", Duration::from_secs(5400))]
+    #[case::hms("01:30:00", Duration::from_secs(5400))]
+    fn test_parse_implicit_unit(#[case] interval: &str, #[case] expected: Duration) {
+        match DurationParser::new()
+            .implicit_unit(true)
+            .parse(interval)
+        {
+            Ok(actual) => assert_eq!(actual, expected),
+            Err(e) => panic!("Expected Ok, got Err: {:?}", e),
+        }
+    }
+
+    #[rstest]
+    #[case::nanoseconds(DurationParser::new(), "1ns5", "no unit smaller than ns for the trailing number: 1ns5")]
+    #[case::colon_nanoseconds(DurationParser::new(), "1:5ns", "no unit smaller than ns for the trailing number: 1:5ns")]
+    #[case::colon_bounded(DurationParser::new(), "1:60h", "invalid duration")]
+    #[case::colon_bounded_weeks(DurationParser::new(), "1:7w", "invalid duration")]
+    #[case::implicit_unit_not_allowed(DurationParser::new().units([Unit::Hours]), "1h30", "invalid unit: m")]
+    #[case::strict(DurationParser::new().strict(true), "1h90", "90m is not below the 60m in a h: 1h90")]
+    #[case::business_days(DurationParser::new(), "3bd4", "invalid duration")]
+    #[case::colon_spaced(DurationParser::new(), "1 : 30h", "invalid duration")]
+    fn test_parse_implicit_unit_invalid(#[case] parser: DurationParser, #[case] interval: &str, #[case] expected: &str) {
+        match parser.implicit_unit(true).parse(interval) {
+            Ok(actual) => panic!("Expected Err, got Ok {:?}", actual),
+            Err(e) => assert_eq!(e.to_string(), expected),
+        }
+    }
+
+    #[test]
+    fn test_parse_implicit_unit_forms() {
+        assert_eq!(DurationParser::new().parse("1h30").ok(), Some(Duration::from_secs(3600)));
+        assert!(DurationParser::new().parse("1:30h").is_err());
+
+        let parser = DurationParser::new().implicit_unit(true);
+        assert_eq!(parser.parse_shorthand("1h30").ok(), Some(Duration::from_secs(5400)));
+        assert_eq!(parser.parse_hms("01:30:00").ok(), Some(Duration::from_secs(5400)));
+        assert_eq!(parser.strict_hms(false).parse("2:30m").ok(), Some(Duration::from_secs(150)));
+    }
+
+    #[test]
+    fn test_parse_hms_and_shorthand() {
+        let parser = DurationParser::new().strict_hms(false);
+        assert_eq!(parser.parse_hms("1:30").ok(), Some(Duration::from_secs(5400)));
+        assert!(parser.parse_hms("1h30m").is_err());
+        assert_eq!(parser.parse_shorthand("1h30m").ok(), Some(Duration::from_secs(5400)));
+        assert!(parser.parse_shorthand("1:30").is_err());
+    }
+
+    #[test]
+    fn test_parse_all() {
+        let parser = DurationParser::new()
+            .case_sensitive(false)
+            .strict_hms(false);
+        assert_eq!(parser.parse_all("2D and 1:30").ok(), Some(vec![Duration::from_secs(2 * 86400), Duration::from_secs(5400)]));
+        assert!(parser.parse_all("nothing here").is_err());
+    }
+
+    #[test]
+    fn test_with_options() {
+        let options = ParserOptions {
+            units: vec![Unit::Seconds],
+            ..ParserOptions::default()
+        };
+        let parser = DurationParser::with_options(options.clone());
+        assert_eq!(parser.options(), &options);
+        assert_eq!(parser.parse("90s").ok(), Some(Duration::from_secs(90)));
+        assert!(parser.parse("1m").is_err());
+    }
+}
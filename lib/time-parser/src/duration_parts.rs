@@ -0,0 +1,291 @@
+use std::fmt;
+use std::ops::Range;
+use std::time::Duration;
+
+use pest::Parser;
+use pest_derive::Parser;
+
+use crate::{Error, Result, Unit};
+
+#[derive(Parser)]
+#[grammar = "grammars/intervals.pest"]
+struct DurationPartsParser;
+
+/// Which syntax a duration was written in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DurationStyle {
+    /// `1h30m`
+    Shorthand,
+    /// `01:30:00`
+    Hms,
+}
+
+/// One quantity of a parsed duration, such as `90m` or the `30` of `01:30:00`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DurationComponent {
+    pub quantity: u64,
+    pub unit: Unit,
+    /// Where the component was written, as byte offsets into the parsed input.
+    pub span: Range<usize>,
+    digits: String,
+    separator: String,
+    unit_text: String,
+}
+
+impl DurationComponent {
+    /// The unit as it was spelled, such as `minutes`; empty for HMS components.
+    pub fn unit_text(&self) -> &str {
+        &self.unit_text
+    }
+
+    /// The quantity as it was written, or as a plain number once `quantity` has been changed.
+    fn digits(&self, style: DurationStyle) -> String {
+        if self.digits.replace('_', "").parse::<u64>().ok() == Some(self.quantity) {
+            return self.digits.clone();
+        }
+        match style {
+            DurationStyle::Shorthand => self.quantity.to_string(),
+            DurationStyle::Hms => format!("{:02}", self.quantity),
+        }
+    }
+}
+
+/// A parsed duration that keeps each component as written, so `90m` stays `90m` instead of becoming `1h30m`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DurationParts {
+    pub style: DurationStyle,
+    /// The components in input order.
+    pub components: Vec<DurationComponent>,
+    source: String,
+    offset: usize,
+}
+
+impl DurationParts {
+    /// The total of all components, or an error when it does not fit in a [`Duration`].
+    pub fn to_duration(&self) -> Result<Duration> {
+        self.components
+            .iter()
+            .try_fold(Duration::ZERO, |total, component| {
+                component
+                    .unit
+                    .checked_duration(component.quantity)
+                    .and_then(|duration| total.checked_add(duration))
+            })
+            .ok_or_else(|| {
+                tracing::trace!("Duration parts overflow: {}", self.source);
+                Error::overflow(&self.source)
+            })
+    }
+}
+
+impl TryFrom<&DurationParts> for Duration {
+    type Error = Error;
+
+    fn try_from(parts: &DurationParts) -> Result<Self> {
+        parts.to_duration()
+    }
+}
+
+/// Writes each component with its original spelling and the text between components as it was in the input.
+impl fmt::Display for DurationParts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut previous_end = None;
+        for component in &self.components {
+            if let Some(previous_end) = previous_end {
+                let gap = (previous_end - self.offset)..(component.span.start - self.offset);
+                f.write_str(self.source.get(gap).unwrap_or_default())?;
+            }
+            write!(f, "{}{}{}", component.digits(self.style), component.separator, component.unit_text)?;
+            previous_end = Some(component.span.end);
+        }
+        Ok(())
+    }
+}
+
+/// Parses a duration as [`crate::parse_duration`] does, keeping each component's quantity, unit and position.
+///
+/// The whole input must be the duration, though whitespace around it is allowed and counted in the spans.
+#[inline(always)]
+pub fn parse_duration_parts(interval: impl AsRef<str>) -> Result<DurationParts> {
+    let interval = interval.as_ref();
+    let parse_result = DurationPartsParser::parse(Rule::duration_whole, interval).map_err(|_e| Error::invalid_duration(interval))?;
+
+    let Some(duration) = parse_result
+        .flat_map(|pair| pair.into_inner())
+        .find(|pair| pair.as_rule() == Rule::duration)
+    else {
+        unreachable!("Duration must be parsed");
+    };
+    let (source, offset) = (duration.as_str().to_string(), duration.as_span().start());
+    let Some(form) = duration.into_inner().next() else {
+        unreachable!("Duration must have a form");
+    };
+
+    let mut components = Vec::new();
+    let style = match form.as_rule() {
+        Rule::duration_shorthand => {
+            let mut inner_pairs = form.into_inner();
+            while let (Some(digits_pair), Some(unit_pair)) = (inner_pairs.next(), inner_pairs.next()) {
+                let unit = unit_pair.as_str().parse::<Unit>().map_err(|_e| {
+                    tracing::trace!("Unit without a fixed length: {}", unit_pair.as_str());
+                    Error::invalid_duration(interval)
+                })?;
+                let (digits, unit_span) = (digits_pair.as_span(), unit_pair.as_span());
+                components.push(DurationComponent {
+                    quantity: parse_quantity(digits.as_str(), interval)?,
+                    unit,
+                    span: digits.start()..unit_span.end(),
+                    digits: digits.as_str().to_string(),
+                    separator: interval[digits.end()..unit_span.start()].to_string(),
+                    unit_text: unit_span.as_str().to_string(),
+                });
+            }
+            DurationStyle::Shorthand
+        }
+        Rule::duration_hms => {
+            for (pair, unit) in form
+                .into_inner()
+                .zip([Unit::Hours, Unit::Minutes, Unit::Seconds])
+            {
+                let span = pair.as_span();
+                components.push(DurationComponent {
+                    quantity: parse_quantity(span.as_str(), interval)?,
+                    unit,
+                    span: span.start()..span.end(),
+                    digits: span.as_str().to_string(),
+                    separator: String::new(),
+                    unit_text: String::new(),
+                });
+            }
+            if components
+                .iter()
+                .skip(1)
+                .any(|component| component.quantity > 59)
+            {
+                tracing::trace!("Invalid minutes or seconds: {}", interval);
+                return Err(Error::invalid_duration(interval));
+            }
+            DurationStyle::Hms
+        }
+        _ => unreachable!("Unexpected rule in duration parts parsing: {:?}", form.as_rule()),
+    };
+
+    Ok(DurationParts { style, components, source, offset })
+}
+
+#[inline(always)]
+fn parse_quantity(digits: &str, interval: &str) -> Result<u64> {
+    digits
+        .replace('_', "")
+        .parse::<u64>()
+        .map_err(|_e| {
+            tracing::trace!("Invalid quantity: {}", digits);
+            Error::invalid_duration(interval)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::minutes("90m", DurationStyle::Shorthand, &[(90, Unit::Minutes)])]
+    #[case::repeated_units("30m1h", DurationStyle::Shorthand, &[(30, Unit::Minutes), (1, Unit::Hours)])]
+    #[case::long_names("1 hour 30 minutes", DurationStyle::Shorthand, &[(1, Unit::Hours), (30, Unit::Minutes)])]
+    #[case::grouped_digits("1_800_000ms", DurationStyle::Shorthand, &[(1_800_000, Unit::Milliseconds)])]
+    #[case::hms("01:30:00", DurationStyle::Hms, &[(1, Unit::Hours), (30, Unit::Minutes), (0, Unit::Seconds)])]
+    fn test_parse_duration_parts(#[case] interval: &str, #[case] style: DurationStyle, #[case] expected: &[(u64, Unit)]) {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .try_init()
+            .ok();
+
+        match parse_duration_parts(interval) {
+            Ok(actual) => {
+                let components: Vec<_> = actual
+                    .components
+                    .iter()
+                    .map(|component| (component.quantity, component.unit))
+                    .collect();
+                assert_eq!(actual.style, style);
+                assert_eq!(components, expected);
+                assert_eq!(actual.to_duration().ok(), crate::parse_duration(interval).ok());
+            }
+            Err(e) => panic!("Expected Ok, got Err: {:?}", e),
+        }
+    }
+
+    #[rstest]
+    #[case::minutes("90m")]
+    #[case::unordered("30m1h")]
+    #[case::spaced("1 hour 30 minutes")]
+    #[case::grouped_digits("1_800_000ms")]
+    #[case::mixed_spacing("1h  30 m 5s")]
+    #[case::hms("01:30:00")]
+    fn test_display_round_trip(#[case] interval: &str) {
+        let parts = parse_duration_parts(interval).expect("duration must be valid for this test");
+        assert_eq!(parts.to_string(), interval);
+    }
+
+    #[rstest]
+    #[case::shorthand("1 hour 30 minutes", 45, "1 hour 45 minutes")]
+    #[case::grouped_digits("1h 1_000ms", 2500, "1h 2500ms")]
+    #[case::hms("01:30:00", 5, "01:05:00")]
+    fn test_display_after_edit(#[case] interval: &str, #[case] quantity: u64, #[case] expected: &str) {
+        let mut parts = parse_duration_parts(interval).expect("duration must be valid for this test");
+        parts.components[1].quantity = quantity;
+        assert_eq!(parts.to_string(), expected);
+    }
+
+    #[test]
+    fn test_spans() {
+        let parts = parse_duration_parts("1 hour 30m").expect("duration must be valid for this test");
+        let spans: Vec<_> = parts
+            .components
+            .iter()
+            .map(|component| component.span.clone())
+            .collect();
+        assert_eq!(spans, vec![0..6, 7..10]);
+        assert_eq!(parts.components[0].unit_text(), "hour");
+    }
+
+    #[test]
+    fn test_surrounding_whitespace() {
+        let parts = parse_duration_parts(" 5m 30s ").expect("duration must be valid for this test");
+        let spans: Vec<_> = parts
+            .components
+            .iter()
+            .map(|component| component.span.clone())
+            .collect();
+        assert_eq!(spans, vec![1..3, 4..7]);
+        assert_eq!(parts.to_string(), "5m 30s");
+    }
+
+    #[test]
+    fn test_to_duration_overflow() {
+        let parts = parse_duration_parts("18446744073709551615w").expect("duration must be valid for this test");
+        assert_eq!(
+            parts
+                .to_duration()
+                .expect_err("duration must overflow for this test")
+                .to_string(),
+            Error::overflow("18446744073709551615w").to_string()
+        );
+    }
+
+    #[rstest]
+    #[case::empty("")]
+    #[case::business_days("3bd")]
+    #[case::text("soon")]
+    #[case::hms_minutes("01:75:00")]
+    #[case::trailing_text("5m junk")]
+    #[case::leading_text("in 5m")]
+    fn test_parse_duration_parts_invalid(#[case] interval: &str) {
+        match parse_duration_parts(interval) {
+            Ok(actual) => panic!("Expected Err, got Ok {:?}", actual),
+            Err(e) => assert_eq!(e.to_string(), Error::invalid_duration(interval).to_string()),
+        }
+    }
+}
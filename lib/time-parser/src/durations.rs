@@ -1,278 +1,55 @@
 use std::time::Duration;
 
-use pest::Parser;
-use pest_derive::Parser;
-
-use crate::{Error, Result, NANOSECONDS_PER_MICROSECOND, NANOSECONDS_PER_MILLISECOND, SECONDS_PER_DAY, SECONDS_PER_HOUR, SECONDS_PER_MINUTE, SECONDS_PER_WEEK};
-
-#[derive(Parser)]
-#[grammar = "grammars/intervals.pest"]
-struct DurationParser;
+use crate::{DurationConstraint, DurationParser, Result, Unit};
 
 #[inline(always)]
 pub fn parse_duration_hms(interval: impl AsRef<str>) -> Result<Duration> {
-    let parse_result = DurationParser::parse(Rule::duration_hms, interval.as_ref()).map_err(|_e| Error::invalid_duration(interval.as_ref()))?;
-
-    let mut parsed = false;
-    let mut seconds = 0;
-    let mut minutes = 0;
-    let mut hours = 0;
-
-    for pair in parse_result {
-        if pair.as_rule() == Rule::duration_hms {
-            (hours, minutes, seconds, parsed) = parse_hms_inner(pair, interval.as_ref())?;
-        } else {
-            tracing::trace!("Unexpected top-level rule: {:?}", pair.as_rule());
-        }
-    }
-
-    if !parsed {
-        tracing::trace!("No valid hms values found in interval: {}", interval.as_ref());
-        return Err(Error::invalid_duration(interval.as_ref()));
-    }
-
-    let total_seconds = hours * SECONDS_PER_HOUR + minutes * SECONDS_PER_MINUTE + seconds;
-
-    Ok(Duration::from_secs(total_seconds))
-}
-
-#[inline(always)]
-fn parse_hms_inner(pair: pest::iterators::Pair<Rule>, interval: &str) -> Result<(u64, u64, u64, bool)> {
-    let mut parsed = false;
-    let mut hours = 0;
-    let mut minutes = 0;
-    let mut seconds = 0;
-
-    for inner_pair in pair.into_inner() {
-        match inner_pair.as_rule() {
-            Rule::hms_hour => {
-                hours = parse_hour(inner_pair, interval)?;
-                parsed = true;
-            }
-            Rule::hms_minute => {
-                minutes = parse_minute(inner_pair, interval)?;
-            }
-            Rule::hms_second => {
-                seconds = parse_second(inner_pair, interval)?;
-            }
-            _ => {
-                tracing::trace!("Unexpected rule: {:?}", inner_pair.as_rule());
-            }
-        }
-    }
-
-    Ok((hours, minutes, seconds, parsed))
-}
-
-#[inline(always)]
-fn parse_hour(pair: pest::iterators::Pair<Rule>, interval: &str) -> Result<u64> {
-    let value = pair.as_str().parse::<u64>().map_err(|_| {
-        tracing::trace!("Invalid hour value: {}", pair.as_str());
-        Error::invalid_duration(interval)
-    })?;
-    Ok(value)
-}
-
-#[inline(always)]
-fn parse_minute(pair: pest::iterators::Pair<Rule>, interval: &str) -> Result<u64> {
-    let value = pair.as_str().parse::<u64>().map_err(|_| {
-        tracing::trace!("Invalid minute value: {}", pair.as_str());
-        Error::invalid_duration(interval)
-    })?;
-
-    if value > 59 {
-        tracing::trace!("Invalid minute value: {}", pair.as_str());
-        return Err(Error::invalid_duration(interval));
-    }
-    Ok(value)
-}
-
-#[inline(always)]
-fn parse_second(pair: pest::iterators::Pair<Rule>, interval: &str) -> Result<u64> {
-    let value = pair.as_str().parse::<u64>().map_err(|_| {
-        tracing::trace!("Invalid second value: {}", pair.as_str());
-        Error::invalid_duration(interval)
-    })?;
-
-    if value > 59 {
-        tracing::trace!("Invalid second value: {}", pair.as_str());
-        return Err(Error::invalid_duration(interval));
-    }
-    Ok(value)
+    DurationParser::default().parse_hms(interval)
 }
 
 #[inline(always)]
 pub fn parse_duration_shorthand(interval: impl AsRef<str>) -> Result<Duration> {
-    let parse_result = DurationParser::parse(Rule::duration_shorthand, interval.as_ref()).map_err(|_e| Error::invalid_duration(interval.as_ref()))?;
-
-    let mut delta_seconds = 0;
-    let mut delta_nanoseconds: u64 = 0;
-
-    for pair in parse_result {
-        if pair.as_rule() == Rule::duration_shorthand {
-            let (seconds, nanoseconds, parsed) = parse_shorthand_inner(pair, interval.as_ref())?;
-            if parsed {
-                delta_seconds += seconds;
-                delta_nanoseconds += nanoseconds;
-            }
-        } else {
-            tracing::trace!("Unexpected top-level rule: {:?}", pair.as_rule());
-        }
-    }
-
-    Ok(Duration::new(delta_seconds, delta_nanoseconds as u32))
+    DurationParser::default().parse_shorthand(interval)
 }
 
+/// Parses a quantity for [`crate::WorkCalendar`], splitting it into business days and a duration of working time.
+///
+/// Unlike [`parse_duration`], the whole input must be a quantity.
 #[inline(always)]
-fn parse_shorthand_inner(pair: pest::iterators::Pair<Rule>, interval: &str) -> Result<(u64, u64, bool)> {
-    let mut parsed = false;
-    let mut delta_seconds = 0;
-    let mut delta_nanoseconds: u64 = 0;
-
-    let mut inner_pairs = pair.into_inner().peekable();
-    while let Some(inner_pair) = inner_pairs.next() {
-        if inner_pair.as_rule() == Rule::shorthand_digits {
-            let digits_str = inner_pair.as_str();
-            let value = digits_str
-                .replace('_', "")
-                .parse::<u64>()
-                .map_err(|_| Error::invalid_duration(interval))?;
-
-            if let Some(unit_pair) = inner_pairs.next() {
-                let (delta_sec, delta_nano) = match unit_pair.as_rule() {
-                    Rule::units_weeks => (value * SECONDS_PER_WEEK, 0),
-                    Rule::units_days => (value * SECONDS_PER_DAY, 0),
-                    Rule::units_hours => (value * SECONDS_PER_HOUR, 0),
-                    Rule::units_minutes => (value * SECONDS_PER_MINUTE, 0),
-                    Rule::units_seconds => (value, 0),
-                    Rule::units_milliseconds => (0, value * NANOSECONDS_PER_MILLISECOND),
-                    Rule::units_microseconds => (0, value * NANOSECONDS_PER_MICROSECOND),
-                    Rule::units_nanoseconds => (0, value),
-                    _ => {
-                        tracing::trace!("Unexpected unit: {:?}", unit_pair.as_str());
-                        return Err(Error::invalid_duration(interval));
-                    }
-                };
-                delta_seconds += delta_sec;
-                delta_nanoseconds += delta_nano;
-                parsed = true;
-            } else {
-                tracing::trace!("Missing unit for shorthand: {}", digits_str);
-                return Err(Error::invalid_duration(interval));
-            }
-        } else {
-            tracing::trace!("Unexpected rule: {:?}", inner_pair.as_rule());
-        }
-    }
-
-    Ok((delta_seconds, delta_nanoseconds, parsed))
+pub(crate) fn parse_business_duration(interval: impl AsRef<str>) -> Result<(u64, Duration)> {
+    DurationParser::default().parse_business(interval)
 }
 
 #[inline(always)]
 pub fn parse_duration(interval: impl AsRef<str>) -> Result<Duration> {
-    let parse_result = DurationParser::parse(Rule::duration, interval.as_ref()).map_err(|_e| Error::invalid_duration(interval.as_ref()))?;
-
-    for pair in parse_result {
-        if pair.as_rule() == Rule::duration {
-            return parse_duration_inner(pair, interval.as_ref());
-        }
-    }
-
-    tracing::trace!("No valid duration values found in input: {}", interval.as_ref());
-    Err(Error::invalid_duration(interval.as_ref()))
+    DurationParser::default().parse(interval)
 }
 
+/// Parses a duration as [`parse_duration`] does and checks it against `constraint`.
 #[inline(always)]
-fn parse_duration_inner(pair: pest::iterators::Pair<Rule>, input: &str) -> Result<Duration> {
-    let mut delta_seconds = 0;
-    let mut delta_nanoseconds: u64 = 0;
-    let mut parsed = false;
-
-    for inner_pair in pair.into_inner() {
-        match inner_pair.as_rule() {
-            Rule::duration_shorthand => {
-                let (seconds, nanoseconds, _) = parse_shorthand_inner(inner_pair, input)?;
-                delta_seconds += seconds;
-                delta_nanoseconds += nanoseconds;
-                parsed = true;
-            }
-            Rule::duration_hms => {
-                let (hours, minutes, seconds, _) = parse_hms_inner(inner_pair, input)?;
-                delta_seconds += hours * SECONDS_PER_HOUR + minutes * SECONDS_PER_MINUTE + seconds;
-                parsed = true;
-            }
-            _ => unreachable!("Unexpected rule in duration parsing: {:?}", inner_pair.as_rule()),
-        }
-    }
-
-    if !parsed {
-        tracing::trace!("No valid duration values found in interval: {}", input);
-        return Err(Error::invalid_duration(input));
-    }
-
-    Ok(Duration::new(delta_seconds, delta_nanoseconds as u32))
+pub fn parse_duration_bounded(interval: impl AsRef<str>, constraint: &DurationConstraint) -> Result<Duration> {
+    DurationParser::default()
+        .constraint(constraint.clone())
+        .parse(interval)
 }
 
 #[inline(always)]
 pub fn parse_durations(string: impl AsRef<str>) -> Result<Vec<Duration>> {
-    let parse_result = DurationParser::parse(Rule::durations, string.as_ref()).map_err(|_e| Error::invalid_duration(string.as_ref()))?;
-
-    let mut durations = Vec::new();
-
-    for pair in parse_result {
-        if pair.as_rule() == Rule::duration {
-            durations.push(parse_duration_inner(pair, string.as_ref())?);
-        } else {
-            unreachable!("Unexpected rule in durations parsing: {:?}", pair.as_rule());
-        }
-    }
-
-    if durations.is_empty() {
-        tracing::trace!("No valid duration values found in input: {}", string.as_ref());
-        return Err(Error::invalid_duration(string.as_ref()));
-    }
-
-    Ok(durations)
+    DurationParser::default().parse_all(string)
 }
 
 #[inline(always)]
 pub fn duration_as_string(duration: &Duration) -> String {
-    let total_seconds = duration.as_secs();
-    let nanos = duration.subsec_nanos() as u64;
-
-    let weeks = total_seconds / SECONDS_PER_WEEK;
-    let days = (total_seconds % SECONDS_PER_WEEK) / SECONDS_PER_DAY;
-    let hours = (total_seconds % SECONDS_PER_DAY) / SECONDS_PER_HOUR;
-    let minutes = (total_seconds % SECONDS_PER_HOUR) / SECONDS_PER_MINUTE;
-    let seconds = total_seconds % SECONDS_PER_MINUTE;
-    let milliseconds = nanos / NANOSECONDS_PER_MILLISECOND;
-    let microseconds = (nanos % NANOSECONDS_PER_MILLISECOND) / NANOSECONDS_PER_MICROSECOND;
-    let nanoseconds = nanos % NANOSECONDS_PER_MICROSECOND;
+    let mut remaining = duration.as_nanos();
 
     let mut parts = Vec::new();
-    if weeks > 0 {
-        parts.push(format!("{}w", weeks));
-    }
-    if days > 0 {
-        parts.push(format!("{}d", days));
-    }
-    if hours > 0 {
-        parts.push(format!("{}h", hours));
-    }
-    if minutes > 0 {
-        parts.push(format!("{}m", minutes));
-    }
-    if seconds > 0 {
-        parts.push(format!("{}s", seconds));
-    }
-    if milliseconds > 0 {
-        parts.push(format!("{}ms", milliseconds));
-    }
-    if microseconds > 0 {
-        parts.push(format!("{}µs", microseconds));
-    }
-    if nanoseconds > 0 {
-        parts.push(format!("{}ns", nanoseconds));
+    for unit in Unit::ALL.iter().rev() {
+        let length = unit.nanoseconds() as u128;
+        let count = remaining / length;
+        remaining %= length;
+        if count > 0 {
+            parts.push(format!("{}{}", count, unit.symbol()));
+        }
     }
     parts.join("")
 }
@@ -282,11 +59,14 @@ mod tests {
     use rstest::rstest;
 
     use super::*;
+    use crate::constants::*;
+    use crate::Error;
 
     #[rstest]
     #[case::valid_shorthand_2d3h4m("2d3h4m", Ok(Duration::from_secs(SECONDS_PER_DAY * 2 + SECONDS_PER_HOUR * 3 + SECONDS_PER_MINUTE * 4)))]
     #[case::valid_shorthand_1w2d3h4m5s6ms7us8ns("1w2d3h4m5s6ms7us8ns", Ok(Duration::new(SECONDS_PER_WEEK + 2 * SECONDS_PER_DAY + 3 * SECONDS_PER_HOUR + 4 * SECONDS_PER_MINUTE + 5, (6 * NANOSECONDS_PER_MILLISECOND + 7 * NANOSECONDS_PER_MICROSECOND + 8) as u32)))]
     #[case::valid_shorthand_600ms("600ms", Ok(Duration::new(0, (600 * NANOSECONDS_PER_MILLISECOND) as u32)))]
+    #[case::valid_shorthand_5000ms("5000ms", Ok(Duration::from_secs(5)))]
     #[case::valid_hms_02_03_04("02:03:04", Ok(Duration::from_secs(SECONDS_PER_HOUR * 2 + SECONDS_PER_MINUTE * 3 + 4)))]
     #[case::invalid_version("1.2.3", Err(Error::invalid_duration("1.2.3")))]
     fn test_parse_duration(#[case] interval: &str, #[case] expected: Result<Duration>) {
@@ -363,6 +143,7 @@ mod tests {
     #[case::invalid_shorthand_1_day_caps("1 DAY", Err(Error::invalid_duration("1 DAY")))]
     #[case::invalid_shorthand_02_03_04("02:03:04", Err(Error::invalid_duration("02:03:04")))]
     #[case::invalid_version("1.2.3", Err(Error::invalid_duration("1.2.3")))]
+    #[case::invalid_shorthand_business_weeks("2 business weeks", Err(Error::invalid_duration("2 business weeks")))]
     fn test_parse_shorthand_duration(#[case] interval: &str, #[case] expected: Result<Duration>) {
         tracing_subscriber::fmt()
             .with_max_level(tracing::Level::TRACE)
@@ -408,6 +189,7 @@ mod tests {
     #[case::valid_durations_2d_and_3h("2d and 3h", &[Duration::from_secs(SECONDS_PER_DAY * 2), Duration::from_secs(SECONDS_PER_HOUR * 3)])]
     #[case::valid_durations_2d_and_3h_and_4m("2d and 3h and 4m", &[Duration::from_secs(SECONDS_PER_DAY * 2), Duration::from_secs(SECONDS_PER_HOUR * 3), Duration::from_secs(SECONDS_PER_MINUTE * 4)])]
     #[case::valid_durations_5d_and_01_02_03("5d and 01:02:03", &[Duration::from_secs(SECONDS_PER_DAY * 5), Duration::from_secs(SECONDS_PER_HOUR * 1 + SECONDS_PER_MINUTE * 2 + 3)])]
+    #[allow(clippy::identity_op)]
     fn test_parse_durations(#[case] interval: &str, #[case] expected: &[Duration]) {
         tracing_subscriber::fmt()
             .with_max_level(tracing::Level::TRACE)
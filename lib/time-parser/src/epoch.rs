@@ -0,0 +1,241 @@
+use std::fmt;
+
+use chrono::{DateTime, Datelike, TimeZone, Utc};
+use pest::Parser;
+use pest_derive::Parser;
+
+use crate::{Error, Result};
+
+#[derive(Parser)]
+#[grammar = "grammars/epoch.pest"]
+struct EpochParser;
+
+const NANOSECONDS_PER_SECOND: i128 = 1_000_000_000;
+const MAX_INTEGER_DIGITS: usize = 20;
+const MIN_YEAR: i32 = 1;
+const MAX_YEAR: i32 = 9999;
+
+/// The unit of a Unix epoch timestamp.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EpochUnit {
+    Seconds,
+    Milliseconds,
+    Microseconds,
+    Nanoseconds,
+}
+
+impl EpochUnit {
+    /// Guesses the unit from the magnitude of the integer part.
+    ///
+    /// Seconds cover anything below 10^11 (until the year 5138), milliseconds below 10^14, microseconds below 10^17 and
+    /// nanoseconds the rest, so every unit resolves correctly for timestamps between 1973 and 5138.
+    pub fn detect(integer: u128) -> Self {
+        match integer {
+            0..100_000_000_000 => Self::Seconds,
+            100_000_000_000..100_000_000_000_000 => Self::Milliseconds,
+            100_000_000_000_000..100_000_000_000_000_000 => Self::Microseconds,
+            _ => Self::Nanoseconds,
+        }
+    }
+
+    fn nanoseconds(&self) -> i128 {
+        match self {
+            Self::Seconds => NANOSECONDS_PER_SECOND,
+            Self::Milliseconds => 1_000_000,
+            Self::Microseconds => 1_000,
+            Self::Nanoseconds => 1,
+        }
+    }
+
+    /// The number of fractional digits the unit can carry, each worth a nanosecond at the last place.
+    fn fraction_digits(&self) -> usize {
+        match self {
+            Self::Seconds => 9,
+            Self::Milliseconds => 6,
+            Self::Microseconds => 3,
+            Self::Nanoseconds => 0,
+        }
+    }
+}
+
+impl fmt::Display for EpochUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Seconds => "s",
+            Self::Milliseconds => "ms",
+            Self::Microseconds => "us",
+            Self::Nanoseconds => "ns",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Parses a Unix epoch timestamp, detecting its unit from its magnitude (see [`EpochUnit::detect`]).
+///
+/// Accepts an optional `@` prefix as GNU `date` does (`@1729252800`), a sign, and a fractional part (`1729252800.123`).
+/// Timestamps outside the years 1 to 9999 are rejected.
+#[inline(always)]
+pub fn parse_epoch(input: impl AsRef<str>) -> Result<DateTime<Utc>> {
+    parse_epoch_inner(input.as_ref(), None)
+}
+
+/// Parses a Unix epoch timestamp in the given unit, skipping magnitude detection.
+#[inline(always)]
+pub fn parse_epoch_as(input: impl AsRef<str>, unit: EpochUnit) -> Result<DateTime<Utc>> {
+    parse_epoch_inner(input.as_ref(), Some(unit))
+}
+
+/// Formats a datetime as an integer Unix epoch timestamp in the given unit, rounding toward the past.
+#[inline(always)]
+pub fn epoch_as_string<Tz: TimeZone>(datetime: &DateTime<Tz>, unit: EpochUnit) -> String {
+    let nanoseconds = datetime.timestamp() as i128 * NANOSECONDS_PER_SECOND + datetime.timestamp_subsec_nanos() as i128;
+    nanoseconds
+        .div_euclid(unit.nanoseconds())
+        .to_string()
+}
+
+#[inline(always)]
+fn parse_epoch_inner(input: &str, unit: Option<EpochUnit>) -> Result<DateTime<Utc>> {
+    let parse_result = EpochParser::parse(Rule::epoch, input.trim()).map_err(|_e| Error::invalid_time(input))?;
+
+    let (mut negative, mut integer, mut fraction) = (false, "", "");
+    for pair in parse_result.flat_map(|pair| pair.into_inner()) {
+        match pair.as_rule() {
+            Rule::epoch_sign => negative = pair.as_str() == "-",
+            Rule::epoch_integer => integer = pair.as_str(),
+            Rule::epoch_fraction => fraction = pair.as_str(),
+            Rule::EOI => {}
+            _ => unreachable!("Unexpected rule in epoch parsing: {:?}", pair.as_rule()),
+        }
+    }
+
+    let integer = integer.trim_start_matches('0');
+    if integer.len() > MAX_INTEGER_DIGITS {
+        tracing::trace!("Epoch timestamp has too many digits: {}", input);
+        return Err(Error::invalid_time(input));
+    }
+    let integer = integer.parse::<u128>().unwrap_or(0);
+
+    let unit = unit.unwrap_or_else(|| EpochUnit::detect(integer));
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.len() > unit.fraction_digits() {
+        tracing::trace!("Epoch fraction is finer than a nanosecond for unit {}: {}", unit, input);
+        return Err(Error::invalid_time(input));
+    }
+    let fraction_nanoseconds = if fraction.is_empty() {
+        0
+    } else {
+        fraction.parse::<i128>().unwrap_or(0) * 10i128.pow((unit.fraction_digits() - fraction.len()) as u32)
+    };
+
+    let mut nanoseconds = integer as i128 * unit.nanoseconds() + fraction_nanoseconds;
+    if negative {
+        nanoseconds = -nanoseconds;
+    }
+
+    let seconds = i64::try_from(nanoseconds.div_euclid(NANOSECONDS_PER_SECOND)).ok();
+    let subsec_nanoseconds = nanoseconds.rem_euclid(NANOSECONDS_PER_SECOND) as u32;
+    seconds
+        .and_then(|seconds| DateTime::from_timestamp(seconds, subsec_nanoseconds))
+        .filter(|datetime| (MIN_YEAR..=MAX_YEAR).contains(&datetime.year()))
+        .ok_or_else(|| {
+            tracing::trace!("Epoch timestamp out of range for unit {}: {}", unit, input);
+            Error::invalid_time(input)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    fn rfc3339(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .expect("datetime must be valid for this test")
+            .to_utc()
+    }
+
+    #[rstest]
+    #[case::seconds("1729252800", "2024-10-18T12:00:00Z")]
+    #[case::gnu_prefix("@1729252800", "2024-10-18T12:00:00Z")]
+    #[case::fractional_seconds("1729252800.123", "2024-10-18T12:00:00.123Z")]
+    #[case::nanosecond_fraction("1729252800.000000001", "2024-10-18T12:00:00.000000001Z")]
+    #[case::trailing_zeros("1729252800.1230000000", "2024-10-18T12:00:00.123Z")]
+    #[case::milliseconds("1729252800123", "2024-10-18T12:00:00.123Z")]
+    #[case::fractional_milliseconds("1729252800123.5", "2024-10-18T12:00:00.1235Z")]
+    #[case::microseconds("1729252800123456", "2024-10-18T12:00:00.123456Z")]
+    #[case::nanoseconds("1729252800123456789", "2024-10-18T12:00:00.123456789Z")]
+    #[case::zero("0", "1970-01-01T00:00:00Z")]
+    #[case::negative("-86400", "1969-12-31T00:00:00Z")]
+    #[case::negative_fraction("@-1.5", "1969-12-31T23:59:58.5Z")]
+    #[case::positive_sign("+60", "1970-01-01T00:01:00Z")]
+    #[case::whitespace(" 1729252800 ", "2024-10-18T12:00:00Z")]
+    fn test_parse_epoch(#[case] input: &str, #[case] expected: &str) {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .try_init()
+            .ok();
+
+        match parse_epoch(input) {
+            Ok(actual) => assert_eq!(actual, rfc3339(expected)),
+            Err(e) => panic!("Expected Ok, got Err: {:?}", e),
+        }
+    }
+
+    #[rstest]
+    #[case::seconds_override("1729252800123", EpochUnit::Seconds, None)]
+    #[case::milliseconds_override("1729252800", EpochUnit::Milliseconds, Some("1970-01-21T00:20:52.800Z"))]
+    #[case::microseconds_override("1729252800", EpochUnit::Microseconds, Some("1970-01-01T00:28:49.252800Z"))]
+    #[case::nanoseconds_override("1729252800", EpochUnit::Nanoseconds, Some("1970-01-01T00:00:01.729252800Z"))]
+    #[case::nanoseconds_fraction("1.5", EpochUnit::Nanoseconds, None)]
+    #[case::after_year_9999("253402300800", EpochUnit::Seconds, None)]
+    #[case::microseconds_fraction("1.001", EpochUnit::Microseconds, Some("1970-01-01T00:00:00.000001001Z"))]
+    fn test_parse_epoch_as(#[case] input: &str, #[case] unit: EpochUnit, #[case] expected: Option<&str>) {
+        let result = parse_epoch_as(input, unit);
+        match expected {
+            Some(expected) => assert_eq!(result.ok(), Some(rfc3339(expected))),
+            None => assert_eq!(
+                result
+                    .expect_err("input must be rejected for this test")
+                    .to_string(),
+                Error::invalid_time(input).to_string()
+            ),
+        }
+    }
+
+    #[rstest]
+    #[case::empty("")]
+    #[case::prefix_only("@")]
+    #[case::letters("17292528OO")]
+    #[case::fraction_only(".5")]
+    #[case::trailing_dot("1729252800.")]
+    #[case::unit_suffix("1729252800s")]
+    #[case::sub_nanosecond("1729252800123456789.5")]
+    #[case::before_year_one("-62135596801")]
+    #[case::too_many_digits("123456789012345678901")]
+    fn test_parse_epoch_invalid(#[case] input: &str) {
+        match parse_epoch(input) {
+            Ok(actual) => panic!("Expected Err, got Ok {:?}", actual),
+            Err(e) => assert_eq!(e.to_string(), Error::invalid_time(input).to_string()),
+        }
+    }
+
+    #[rstest]
+    #[case::seconds("2024-10-18T12:00:00.123456789Z", EpochUnit::Seconds, "1729252800")]
+    #[case::milliseconds("2024-10-18T12:00:00.123456789Z", EpochUnit::Milliseconds, "1729252800123")]
+    #[case::microseconds("2024-10-18T12:00:00.123456789Z", EpochUnit::Microseconds, "1729252800123456")]
+    #[case::nanoseconds("2024-10-18T12:00:00.123456789Z", EpochUnit::Nanoseconds, "1729252800123456789")]
+    #[case::before_epoch("1969-12-31T23:59:58.5Z", EpochUnit::Seconds, "-2")]
+    #[case::before_epoch_milliseconds("1969-12-31T23:59:58.5Z", EpochUnit::Milliseconds, "-1500")]
+    fn test_epoch_as_string(#[case] datetime: &str, #[case] unit: EpochUnit, #[case] expected: &str) {
+        let datetime = rfc3339(datetime);
+        assert_eq!(epoch_as_string(&datetime, unit), expected);
+        assert_eq!(
+            parse_epoch_as(expected, unit)
+                .ok()
+                .map(|parsed| parsed <= datetime),
+            Some(true)
+        );
+    }
+}
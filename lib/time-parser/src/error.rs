@@ -1,3 +1,7 @@
+use std::fmt;
+
+use crate::Unit;
+
 type Value = String;
 
 #[derive(thiserror::Error, Debug)]
@@ -8,9 +12,21 @@ pub enum Error {
     #[error("invalid time")]
     InvalidTime(Value),
 
+    #[error("invalid recurrence: {0}")]
+    InvalidRecurrence(Value),
+
+    #[error("invalid time zone: {0}")]
+    InvalidTimeZone(Value),
+
     #[error("invalid unit: {0}")]
     InvalidUnit(Value),
 
+    #[error("duration overflow: {0}")]
+    Overflow(Value),
+
+    #[error("{0}: {1}")]
+    Strict(StrictViolation, Value),
+
     #[error("{0}")]
     Error(String),
 }
@@ -29,7 +45,52 @@ impl Error {
         Self::InvalidTime(value.to_string())
     }
 
+    pub fn invalid_recurrence(value: impl ToString) -> Self {
+        Self::InvalidRecurrence(value.to_string())
+    }
+
+    pub fn invalid_time_zone(value: impl ToString) -> Self {
+        Self::InvalidTimeZone(value.to_string())
+    }
+
     pub fn invalid_unit(value: impl ToString) -> Self {
         Self::InvalidUnit(value.to_string())
     }
+
+    pub fn overflow(value: impl ToString) -> Self {
+        Self::Overflow(value.to_string())
+    }
+
+    pub fn strict(violation: StrictViolation, value: impl ToString) -> Self {
+        Self::Strict(violation, value.to_string())
+    }
+}
+
+/// Why a duration is not canonical in strict mode.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StrictViolation {
+    /// The same unit appears twice, as in `1h 1h`.
+    RepeatedUnit(Unit),
+    /// A unit follows a smaller one, as in `30m1h`.
+    UnitOrder { unit: Unit, previous: Unit },
+    /// A value reaches the next larger allowed unit, as in `90m` when `h` is allowed.
+    OutOfRange { value: u64, unit: Unit, limit: u64, larger_unit: Unit },
+    /// Whitespace other than a single space, or whitespace around a bare number.
+    Whitespace,
+    /// Input left over after the duration.
+    TrailingInput(String),
+}
+
+impl fmt::Display for StrictViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RepeatedUnit(unit) => write!(f, "repeated unit {}", unit),
+            Self::UnitOrder { unit, previous } => write!(f, "unit {} must come before {}", unit, previous),
+            Self::OutOfRange { value, unit, limit, larger_unit } => {
+                write!(f, "{}{} is not below the {}{} in a {}", value, unit, limit, unit, larger_unit)
+            }
+            Self::Whitespace => f.write_str("whitespace other than a single space"),
+            Self::TrailingInput(tail) => write!(f, "trailing input {:?}", tail),
+        }
+    }
 }
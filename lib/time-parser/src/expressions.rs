@@ -0,0 +1,219 @@
+use std::time::Duration;
+
+use pest::iterators::Pair;
+use pest::Parser;
+use pest_derive::Parser;
+
+use crate::{Error, Result};
+
+#[derive(Parser)]
+#[grammar = "grammars/intervals.pest"]
+#[grammar = "grammars/expressions.pest"]
+struct ExpressionParser;
+
+const NANOSECONDS_PER_SECOND: u128 = 1_000_000_000;
+
+/// An intermediate value: a duration in nanoseconds, or a scalar kept as an exact fraction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Value {
+    Duration(u128),
+    Scalar { numerator: u128, denominator: u128 },
+}
+
+/// Evaluates an arithmetic expression over durations, such as `1h + 30m`, `2 * 15m`, `1d - 1h` or `(1h + 30m) / 4`.
+///
+/// Operands are anything [`crate::parse_duration`] accepts as a whole (shorthand or HMS) and unsigned integer or
+/// decimal scalars.  Durations can be added to and subtracted from each other, and multiplied or divided by scalars;
+/// `*` and `/` bind tighter than `+` and `-`.  Division truncates to the nanosecond.
+///
+/// Overflow, including a result too large for [`Duration`], is reported as [`Error::Overflow`].  A negative result or
+/// division by zero is an error as well.
+#[inline(always)]
+pub fn eval_duration_expr(expression: impl AsRef<str>) -> Result<Duration> {
+    let expression = expression.as_ref();
+    let parse_result = ExpressionParser::parse(Rule::duration_expr, expression.trim()).map_err(|_e| Error::invalid_duration(expression))?;
+
+    let Some(sum) = parse_result
+        .flat_map(|pair| pair.into_inner())
+        .find(|pair| pair.as_rule() == Rule::expr_sum)
+    else {
+        unreachable!("Duration expression must have a sum");
+    };
+
+    match eval_pair(sum, expression)? {
+        Value::Duration(nanoseconds) => {
+            let seconds = u64::try_from(nanoseconds / NANOSECONDS_PER_SECOND).map_err(|_e| {
+                tracing::trace!("Duration expression result too large: {}", expression);
+                Error::overflow(expression)
+            })?;
+            Ok(Duration::new(seconds, (nanoseconds % NANOSECONDS_PER_SECOND) as u32))
+        }
+        Value::Scalar { .. } => {
+            tracing::trace!("Duration expression evaluates to a scalar: {}", expression);
+            Err(Error::invalid_duration(expression))
+        }
+    }
+}
+
+#[inline(always)]
+fn eval_pair(pair: Pair<Rule>, expression: &str) -> Result<Value> {
+    match pair.as_rule() {
+        Rule::expr_sum | Rule::expr_product => {
+            let mut inner_pairs = pair.into_inner();
+            let Some(first) = inner_pairs.next() else {
+                unreachable!("Expression must have an operand");
+            };
+            let mut value = eval_pair(first, expression)?;
+            while let (Some(operator), Some(operand)) = (inner_pairs.next(), inner_pairs.next()) {
+                let operand = eval_pair(operand, expression)?;
+                value = apply(operator.as_rule(), value, operand, expression)?;
+            }
+            Ok(value)
+        }
+        Rule::expr_group => {
+            let Some(sum) = pair.into_inner().next() else {
+                unreachable!("Group must have an expression");
+            };
+            eval_pair(sum, expression)
+        }
+        Rule::expr_duration => {
+            let duration = crate::durations::parse_duration(pair.as_str()).map_err(|_e| Error::invalid_duration(expression))?;
+            Ok(Value::Duration(duration.as_nanos()))
+        }
+        Rule::expr_scalar => Ok(parse_scalar(pair.as_str(), expression)?),
+        _ => unreachable!("Unexpected rule in duration expression: {:?}", pair.as_rule()),
+    }
+}
+
+#[inline(always)]
+fn apply(operator: Rule, left: Value, right: Value, expression: &str) -> Result<Value> {
+    let overflow = || {
+        tracing::trace!("Duration expression overflows: {}", expression);
+        Error::overflow(expression)
+    };
+
+    match (operator, left, right) {
+        (Rule::expr_add, Value::Duration(left), Value::Duration(right)) => left
+            .checked_add(right)
+            .map(Value::Duration)
+            .ok_or_else(overflow),
+        (Rule::expr_sub, Value::Duration(left), Value::Duration(right)) => left
+            .checked_sub(right)
+            .map(Value::Duration)
+            .ok_or_else(|| {
+                tracing::trace!("Duration expression is negative: {}", expression);
+                Error::error(format!("negative duration: {}", expression))
+            }),
+        (Rule::expr_mul, Value::Duration(nanoseconds), Value::Scalar { numerator, denominator }) | (Rule::expr_mul, Value::Scalar { numerator, denominator }, Value::Duration(nanoseconds)) => {
+            nanoseconds
+                .checked_mul(numerator)
+                .map(|product| Value::Duration(product / denominator))
+                .ok_or_else(overflow)
+        }
+        (Rule::expr_div, Value::Duration(nanoseconds), Value::Scalar { numerator, denominator }) => {
+            if numerator == 0 {
+                tracing::trace!("Duration expression divides by zero: {}", expression);
+                return Err(Error::error(format!("division by zero: {}", expression)));
+            }
+            nanoseconds
+                .checked_mul(denominator)
+                .map(|product| Value::Duration(product / numerator))
+                .ok_or_else(overflow)
+        }
+        (
+            Rule::expr_mul,
+            Value::Scalar { numerator, denominator },
+            Value::Scalar {
+                numerator: right_numerator,
+                denominator: right_denominator,
+            },
+        ) => numerator
+            .checked_mul(right_numerator)
+            .zip(denominator.checked_mul(right_denominator))
+            .map(|(numerator, denominator)| Value::Scalar { numerator, denominator })
+            .ok_or_else(overflow),
+        _ => {
+            tracing::trace!("Unsupported operands for {:?}: {}", operator, expression);
+            Err(Error::invalid_duration(expression))
+        }
+    }
+}
+
+/// Parses an integer or decimal into an exact fraction, so `1.5` is 15/10.
+#[inline(always)]
+fn parse_scalar(scalar: &str, expression: &str) -> Result<Value> {
+    let (integer, fraction) = scalar.split_once('.').unwrap_or((scalar, ""));
+    let denominator = u32::try_from(fraction.len())
+        .ok()
+        .and_then(|digits| 10u128.checked_pow(digits));
+    let numerator = format!("{}{}", integer, fraction)
+        .parse::<u128>()
+        .ok();
+    match (numerator, denominator) {
+        (Some(numerator), Some(denominator)) => Ok(Value::Scalar { numerator, denominator }),
+        _ => {
+            tracing::trace!("Scalar too large: {}", scalar);
+            Err(Error::overflow(expression))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::add("1h + 30m", Duration::from_secs(5400))]
+    #[case::add_tight("1h+30m", Duration::from_secs(5400))]
+    #[case::multiply_scalar_first("2 * 15m", Duration::from_secs(1800))]
+    #[case::multiply_scalar_last("15m * 2", Duration::from_secs(1800))]
+    #[case::multiply_sign("15m × 2", Duration::from_secs(1800))]
+    #[case::subtract("1d - 1h", Duration::from_secs(23 * 3600))]
+    #[case::divide("90m / 4", Duration::from_secs(1350))]
+    #[case::divide_truncates("1s / 3", Duration::from_nanos(333_333_333))]
+    #[case::decimal_scalar("1.5 * 1h", Duration::from_secs(5400))]
+    #[case::decimal_divisor("1h / 0.5", Duration::from_secs(7200))]
+    #[case::scalar_product("2 * 3 * 10s", Duration::from_secs(60))]
+    #[case::precedence("1h + 30m * 2", Duration::from_secs(7200))]
+    #[case::parentheses("(1h + 30m) * 2", Duration::from_secs(10800))]
+    #[case::nested_parentheses("((1h - 30m) / 2) + 15m", Duration::from_secs(1800))]
+    #[case::left_associative("1h - 30m - 15m", Duration::from_secs(900))]
+    #[case::hms_operand("01:30:00 + 15m", Duration::from_secs(6300))]
+    #[case::compound_operand("1h30m - 45m", Duration::from_secs(2700))]
+    #[case::spaced_operand("1 h 30 m / 3", Duration::from_secs(1800))]
+    #[case::single_operand("5m", Duration::from_secs(300))]
+    #[case::zero("1h - 1h", Duration::ZERO)]
+    #[case::whitespace(" 1h + 1h ", Duration::from_secs(7200))]
+    fn test_eval_duration_expr(#[case] expression: &str, #[case] expected: Duration) {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .try_init()
+            .ok();
+
+        match eval_duration_expr(expression) {
+            Ok(actual) => assert_eq!(actual, expected),
+            Err(e) => panic!("Expected Ok, got Err: {:?}", e),
+        }
+    }
+
+    #[rstest]
+    #[case::empty("", Error::invalid_duration(""))]
+    #[case::scalar_only("2 * 3", Error::invalid_duration("2 * 3"))]
+    #[case::add_scalar("1h + 30", Error::invalid_duration("1h + 30"))]
+    #[case::multiply_durations("1h * 1h", Error::invalid_duration("1h * 1h"))]
+    #[case::divide_by_duration("1h / 30m", Error::invalid_duration("1h / 30m"))]
+    #[case::dangling_operator("1h +", Error::invalid_duration("1h +"))]
+    #[case::unbalanced("(1h + 30m", Error::invalid_duration("(1h + 30m"))]
+    #[case::negative("1h - 2h", Error::error("negative duration: 1h - 2h"))]
+    #[case::division_by_zero("1h / 0", Error::error("division by zero: 1h / 0"))]
+    #[case::overflow_add("18446744073709551615s + 1s", Error::overflow("18446744073709551615s + 1s"))]
+    #[case::overflow_multiply("18446744073709551615s * 18446744073709551615", Error::overflow("18446744073709551615s * 18446744073709551615"))]
+    fn test_eval_duration_expr_invalid(#[case] expression: &str, #[case] expected: Error) {
+        match eval_duration_expr(expression) {
+            Ok(actual) => panic!("Expected Err, got Ok {:?}", actual),
+            Err(e) => assert_eq!(e.to_string(), expected.to_string()),
+        }
+    }
+}
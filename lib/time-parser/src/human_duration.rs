@@ -0,0 +1,228 @@
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Deref, Sub, SubAssign};
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::{Error, Result, Unit};
+
+/// A [`Duration`] that parses from and displays as a human-readable string, for use in structs and configs.
+///
+/// `"1d2h".parse::<HumanDuration>()` accepts anything [`crate::parse_duration`] does.  Arithmetic follows
+/// [`Duration`], so subtracting a longer duration panics.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HumanDuration(pub Duration);
+
+impl HumanDuration {
+    pub fn into_inner(self) -> Duration {
+        self.0
+    }
+
+    /// The non-zero components from the largest unit down, such as `[(1, Hours), (30, Minutes)]`.
+    fn components(&self) -> Vec<(u64, Unit)> {
+        let mut remaining = self.0.as_nanos();
+        let mut components = Vec::new();
+        for unit in Unit::ALL.into_iter().rev() {
+            let length = unit.nanoseconds() as u128;
+            let count = remaining / length;
+            remaining %= length;
+            if count > 0 {
+                components.push((count as u64, unit));
+            }
+        }
+        components
+    }
+}
+
+impl FromStr for HumanDuration {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        crate::durations::parse_duration(value).map(Self)
+    }
+}
+
+/// Formats as [`crate::duration_as_string`] does (`1h30m`), or `0s` for zero.
+///
+/// The alternate flag spells units out (`{:#}` gives `1 hour 30 minutes`) and a precision keeps only that many of the
+/// largest components, truncating the rest (`{:.1}` gives `1h`).
+impl fmt::Display for HumanDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut components = self.components();
+        if let Some(precision) = f.precision() {
+            components.truncate(precision.max(1));
+        }
+        if components.is_empty() {
+            components.push((0, Unit::Seconds));
+        }
+
+        let alternate = f.alternate();
+        for (index, (count, unit)) in components.into_iter().enumerate() {
+            if !alternate {
+                write!(f, "{}{}", count, unit.symbol())?;
+                continue;
+            }
+            // Aliases start with the plural name followed by the singular one.
+            let name = unit.aliases()[usize::from(count == 1)];
+            let separator = if index == 0 { "" } else { " " };
+            write!(f, "{}{} {}", separator, count, name)?;
+        }
+        Ok(())
+    }
+}
+
+impl Deref for HumanDuration {
+    type Target = Duration;
+
+    fn deref(&self) -> &Duration {
+        &self.0
+    }
+}
+
+impl From<Duration> for HumanDuration {
+    fn from(duration: Duration) -> Self {
+        Self(duration)
+    }
+}
+
+impl From<HumanDuration> for Duration {
+    fn from(duration: HumanDuration) -> Self {
+        duration.0
+    }
+}
+
+impl<T: Into<Duration>> Add<T> for HumanDuration {
+    type Output = Self;
+
+    fn add(self, other: T) -> Self {
+        Self(self.0 + other.into())
+    }
+}
+
+impl<T: Into<Duration>> AddAssign<T> for HumanDuration {
+    fn add_assign(&mut self, other: T) {
+        self.0 += other.into();
+    }
+}
+
+impl<T: Into<Duration>> Sub<T> for HumanDuration {
+    type Output = Self;
+
+    fn sub(self, other: T) -> Self {
+        Self(self.0 - other.into())
+    }
+}
+
+impl<T: Into<Duration>> SubAssign<T> for HumanDuration {
+    fn sub_assign(&mut self, other: T) {
+        self.0 -= other.into();
+    }
+}
+
+impl Sum for HumanDuration {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        Self(iter.map(|duration| duration.0).sum())
+    }
+}
+
+impl<'a> Sum<&'a HumanDuration> for HumanDuration {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        Self(iter.map(|duration| duration.0).sum())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::shorthand("1d2h3m4s", Duration::from_secs(93_784))]
+    #[case::hms("02:03:04", Duration::from_secs(7384))]
+    #[case::subsecond("1s500ms", Duration::from_millis(1500))]
+    fn test_from_str(#[case] input: &str, #[case] expected: Duration) {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .try_init()
+            .ok();
+
+        match input.parse::<HumanDuration>() {
+            Ok(actual) => assert_eq!(*actual, expected),
+            Err(e) => panic!("Expected Ok, got Err: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_from_str_invalid() {
+        match "soon".parse::<HumanDuration>() {
+            Ok(actual) => panic!("Expected Err, got Ok {:?}", actual),
+            Err(e) => assert_eq!(e.to_string(), Error::invalid_duration("soon").to_string()),
+        }
+    }
+
+    #[rstest]
+    #[case::compact(Duration::from_secs(5400), "{}", "1h30m")]
+    #[case::zero(Duration::ZERO, "{}", "0s")]
+    #[case::alternate(Duration::from_secs(5400), "{:#}", "1 hour 30 minutes")]
+    #[case::alternate_singular(Duration::from_secs(3661), "{:#}", "1 hour 1 minute 1 second")]
+    #[case::alternate_zero(Duration::ZERO, "{:#}", "0 seconds")]
+    #[case::precision(Duration::from_secs(5415), "{:.1}", "1h")]
+    #[case::precision_two(Duration::from_secs(5415), "{:.2}", "1h30m")]
+    #[case::alternate_precision(Duration::from_millis(90_500), "{:#.2}", "1 minute 30 seconds")]
+    #[case::subsecond(Duration::from_nanos(1_002_003), "{}", "1ms2µs3ns")]
+    fn test_display(#[case] duration: Duration, #[case] format: &str, #[case] expected: &str) {
+        let duration = HumanDuration(duration);
+        let actual = match format {
+            "{}" => format!("{}", duration),
+            "{:#}" => format!("{:#}", duration),
+            "{:.1}" => format!("{:.1}", duration),
+            "{:.2}" => format!("{:.2}", duration),
+            "{:#.2}" => format!("{:#.2}", duration),
+            _ => unreachable!("Unexpected format in test: {}", format),
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let duration: HumanDuration = "1w2d3h4m5s6ms7us8ns"
+            .parse()
+            .expect("duration must be valid for this test");
+        assert_eq!(duration.to_string().parse::<HumanDuration>().ok(), Some(duration));
+    }
+
+    #[test]
+    fn test_std_traits() {
+        let hour = HumanDuration::from(Duration::from_secs(3600));
+        let half_hour: HumanDuration = "30m"
+            .parse()
+            .expect("duration must be valid for this test");
+
+        assert_eq!((hour + half_hour).to_string(), "1h30m");
+        assert_eq!((hour - Duration::from_secs(600)).to_string(), "50m");
+        assert!(half_hour < hour);
+        assert_eq!(hour.as_secs(), 3600);
+        assert_eq!(Duration::from(hour), Duration::from_secs(3600));
+        assert_eq!(
+            [hour, half_hour]
+                .iter()
+                .sum::<HumanDuration>()
+                .to_string(),
+            "1h30m"
+        );
+        assert_eq!(
+            [hour, hour]
+                .into_iter()
+                .sum::<HumanDuration>()
+                .to_string(),
+            "2h"
+        );
+
+        let mut total = HumanDuration::default();
+        total += hour;
+        total -= half_hour;
+        assert_eq!(total, half_hour);
+        assert!(std::collections::HashSet::from([hour, hour]).len() == 1);
+    }
+}
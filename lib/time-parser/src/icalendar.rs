@@ -0,0 +1,927 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Datelike, Duration as ChronoDuration, Months, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Weekday};
+use pest::iterators::{Pair, Pairs};
+use pest::Parser;
+use pest_derive::Parser;
+
+use crate::calendar::{days_in_month, days_in_year};
+use crate::{Error, Result, SECONDS_PER_DAY, SECONDS_PER_HOUR, SECONDS_PER_MINUTE, SECONDS_PER_WEEK};
+
+// Expansion stops once the recurrence walks past the last year an iCalendar DATE can express.
+const MAX_YEAR: i32 = 9999;
+
+#[derive(Parser)]
+#[grammar = "grammars/icalendar.pest"]
+struct ICalendarParser;
+
+/// Parses an RFC 5545 DURATION value (e.g. `P1W`, `-PT15M`, `P1DT12H`).
+///
+/// Days and weeks are treated as exact multiples of 24 hours.
+#[inline(always)]
+pub fn parse_ical_duration(value: impl AsRef<str>) -> Result<ChronoDuration> {
+    let value = value.as_ref();
+    let parse_result = ICalendarParser::parse(Rule::ical_duration, value).map_err(|_e| Error::invalid_duration(value))?;
+
+    let mut negative = false;
+    let mut total_seconds: i64 = 0;
+
+    for pair in parse_result.flatten() {
+        let seconds_per_unit = match pair.as_rule() {
+            Rule::dur_sign => {
+                negative = pair.as_str() == "-";
+                continue;
+            }
+            Rule::dur_week => SECONDS_PER_WEEK,
+            Rule::dur_day => SECONDS_PER_DAY,
+            Rule::dur_hour => SECONDS_PER_HOUR,
+            Rule::dur_minute => SECONDS_PER_MINUTE,
+            Rule::dur_second => 1,
+            _ => continue,
+        };
+        let digits = pair.into_inner().as_str();
+        total_seconds = digits
+            .parse::<i64>()
+            .ok()
+            .and_then(|quantity| quantity.checked_mul(seconds_per_unit as i64))
+            .and_then(|seconds| total_seconds.checked_add(seconds))
+            .ok_or_else(|| {
+                tracing::trace!("Duration component out of range: {}", digits);
+                Error::invalid_duration(value)
+            })?;
+    }
+
+    let duration = ChronoDuration::try_seconds(total_seconds).ok_or_else(|| Error::invalid_duration(value))?;
+    Ok(if negative { -duration } else { duration })
+}
+
+/// Formats a duration as an RFC 5545 DURATION value.
+///
+/// Whole weeks use the week form (`P2W`); everything else uses days and time (`P1DT2H`).
+/// Sub-second precision cannot be represented and is truncated.
+#[inline(always)]
+pub fn ical_duration_as_string(duration: &ChronoDuration) -> String {
+    let sign = if *duration < ChronoDuration::zero() { "-" } else { "" };
+    let total_seconds = duration.num_seconds().unsigned_abs();
+
+    if total_seconds > 0 && total_seconds.is_multiple_of(SECONDS_PER_WEEK) {
+        return format!("{}P{}W", sign, total_seconds / SECONDS_PER_WEEK);
+    }
+
+    let days = total_seconds / SECONDS_PER_DAY;
+    let hours = (total_seconds % SECONDS_PER_DAY) / SECONDS_PER_HOUR;
+    let minutes = (total_seconds % SECONDS_PER_HOUR) / SECONDS_PER_MINUTE;
+    let seconds = total_seconds % SECONDS_PER_MINUTE;
+
+    let mut value = format!("{}P", sign);
+    if days > 0 {
+        value.push_str(&format!("{}D", days));
+    }
+    if hours > 0 || minutes > 0 || seconds > 0 || days == 0 {
+        value.push('T');
+        if hours > 0 {
+            value.push_str(&format!("{}H", hours));
+        }
+        // RFC 5545 has no way to skip minutes between hours and seconds
+        if minutes > 0 || (hours > 0 && seconds > 0) {
+            value.push_str(&format!("{}M", minutes));
+        }
+        if seconds > 0 || (hours == 0 && minutes == 0) {
+            value.push_str(&format!("{}S", seconds));
+        }
+    }
+    value
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Frequency {
+    Secondly,
+    Minutely,
+    Hourly,
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl fmt::Display for Frequency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Secondly => "SECONDLY",
+            Self::Minutely => "MINUTELY",
+            Self::Hourly => "HOURLY",
+            Self::Daily => "DAILY",
+            Self::Weekly => "WEEKLY",
+            Self::Monthly => "MONTHLY",
+            Self::Yearly => "YEARLY",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A `BYDAY` entry such as `MO`, `2TU` or `-1FR`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct WeekdayNum {
+    pub ordinal: Option<i32>,
+    pub weekday: Weekday,
+}
+
+impl WeekdayNum {
+    pub fn new(weekday: Weekday) -> Self {
+        Self { ordinal: None, weekday }
+    }
+
+    pub fn nth(ordinal: i32, weekday: Weekday) -> Self {
+        Self { ordinal: Some(ordinal), weekday }
+    }
+}
+
+impl fmt::Display for WeekdayNum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(ordinal) = self.ordinal {
+            write!(f, "{}", ordinal)?;
+        }
+        f.write_str(weekday_as_str(self.weekday))
+    }
+}
+
+/// An RFC 5545 RRULE.
+///
+/// `BYWEEKNO` is recognized but rejected as unsupported.  A date-only `UNTIL` is stored as the end of that day
+/// and a trailing `Z` is dropped, so all times are treated as floating local times.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecurrenceRule {
+    pub frequency: Frequency,
+    pub interval: u32,
+    pub count: Option<u32>,
+    pub until: Option<NaiveDateTime>,
+    pub by_second: Vec<u32>,
+    pub by_minute: Vec<u32>,
+    pub by_hour: Vec<u32>,
+    pub by_day: Vec<WeekdayNum>,
+    pub by_month_day: Vec<i32>,
+    pub by_year_day: Vec<i32>,
+    pub by_month: Vec<u32>,
+    pub by_set_pos: Vec<i32>,
+    pub week_start: Weekday,
+}
+
+impl RecurrenceRule {
+    pub fn new(frequency: Frequency) -> Self {
+        Self {
+            frequency,
+            interval: 1,
+            count: None,
+            until: None,
+            by_second: Vec::new(),
+            by_minute: Vec::new(),
+            by_hour: Vec::new(),
+            by_day: Vec::new(),
+            by_month_day: Vec::new(),
+            by_year_day: Vec::new(),
+            by_month: Vec::new(),
+            by_set_pos: Vec::new(),
+            week_start: Weekday::Mon,
+        }
+    }
+
+    pub fn occurrences(&self, dtstart: NaiveDateTime) -> Occurrences {
+        Occurrences::new(self.clone(), dtstart, Vec::new())
+    }
+}
+
+impl FromStr for RecurrenceRule {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        parse_rrule(value)
+    }
+}
+
+impl fmt::Display for RecurrenceRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn join<T: ToString>(values: &[T]) -> String {
+            values
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(",")
+        }
+
+        write!(f, "FREQ={}", self.frequency)?;
+        if let Some(until) = self.until {
+            write!(f, ";UNTIL={}", until.format("%Y%m%dT%H%M%S"))?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={}", count)?;
+        }
+        if self.interval != 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        let parts = [
+            ("BYSECOND", join(&self.by_second)),
+            ("BYMINUTE", join(&self.by_minute)),
+            ("BYHOUR", join(&self.by_hour)),
+            ("BYDAY", join(&self.by_day)),
+            ("BYMONTHDAY", join(&self.by_month_day)),
+            ("BYYEARDAY", join(&self.by_year_day)),
+            ("BYMONTH", join(&self.by_month)),
+            ("BYSETPOS", join(&self.by_set_pos)),
+        ];
+        for (name, values) in parts {
+            if !values.is_empty() {
+                write!(f, ";{}={}", name, values)?;
+            }
+        }
+        if self.week_start != Weekday::Mon {
+            write!(f, ";WKST={}", weekday_as_str(self.week_start))?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses an RRULE value, with or without the leading `RRULE:` property name.
+#[inline(always)]
+pub fn parse_rrule(value: impl AsRef<str>) -> Result<RecurrenceRule> {
+    let value = value.as_ref();
+    let parse_result = ICalendarParser::parse(Rule::rrule, value).map_err(|_e| Error::invalid_recurrence(value))?;
+
+    let mut seen = Vec::new();
+    let mut frequency = None;
+    let mut rule = RecurrenceRule::new(Frequency::Daily);
+
+    for part in parse_result.flat_map(|pair| pair.into_inner()) {
+        let part_rule = part.as_rule();
+        if part_rule == Rule::EOI {
+            continue;
+        }
+        if seen.contains(&part_rule) {
+            tracing::trace!("Repeated rule part: {}", part.as_str());
+            return Err(Error::invalid_recurrence(value));
+        }
+        seen.push(part_rule);
+
+        let inner = part.into_inner();
+        match part_rule {
+            Rule::freq_part => frequency = Some(parse_frequency(inner.as_str())),
+            Rule::until_part => rule.until = Some(parse_ical_date_value(inner.as_str(), true, value)?),
+            Rule::count_part => rule.count = Some(parse_positive(inner.as_str(), value)?),
+            Rule::interval_part => rule.interval = parse_positive(inner.as_str(), value)?,
+            Rule::bysecond_part => rule.by_second = parse_unsigned_list(inner, 0..=59, value)?,
+            Rule::byminute_part => rule.by_minute = parse_unsigned_list(inner, 0..=59, value)?,
+            Rule::byhour_part => rule.by_hour = parse_unsigned_list(inner, 0..=23, value)?,
+            Rule::byday_part => {
+                rule.by_day = inner
+                    .map(|pair| parse_weekday_num(pair, value))
+                    .collect::<Result<_>>()?
+            }
+            Rule::bymonthday_part => rule.by_month_day = parse_signed_list(inner, 31, value)?,
+            Rule::byyearday_part => rule.by_year_day = parse_signed_list(inner, 366, value)?,
+            Rule::bymonth_part => rule.by_month = parse_unsigned_list(inner, 1..=12, value)?,
+            Rule::bysetpos_part => rule.by_set_pos = parse_signed_list(inner, 366, value)?,
+            Rule::wkst_part => rule.week_start = parse_weekday(inner.as_str()),
+            Rule::byweekno_part => {
+                tracing::trace!("BYWEEKNO is not supported: {}", value);
+                return Err(Error::invalid_recurrence(value));
+            }
+            _ => unreachable!("Unexpected rule in rrule parsing: {:?}", part_rule),
+        }
+    }
+
+    let Some(frequency) = frequency else {
+        tracing::trace!("Missing FREQ in rrule: {}", value);
+        return Err(Error::invalid_recurrence(value));
+    };
+    rule.frequency = frequency;
+
+    if rule.count.is_some() && rule.until.is_some() {
+        tracing::trace!("COUNT and UNTIL are mutually exclusive: {}", value);
+        return Err(Error::invalid_recurrence(value));
+    }
+    let ordinals_allowed = matches!(frequency, Frequency::Monthly | Frequency::Yearly);
+    if !ordinals_allowed
+        && rule
+            .by_day
+            .iter()
+            .any(|day| day.ordinal.is_some())
+    {
+        tracing::trace!("BYDAY ordinals require FREQ=MONTHLY or FREQ=YEARLY: {}", value);
+        return Err(Error::invalid_recurrence(value));
+    }
+    if frequency == Frequency::Weekly && !rule.by_month_day.is_empty() {
+        tracing::trace!("BYMONTHDAY is not allowed with FREQ=WEEKLY: {}", value);
+        return Err(Error::invalid_recurrence(value));
+    }
+    if matches!(frequency, Frequency::Daily | Frequency::Weekly | Frequency::Monthly) && !rule.by_year_day.is_empty() {
+        tracing::trace!("BYYEARDAY is not allowed with FREQ={}: {}", frequency, value);
+        return Err(Error::invalid_recurrence(value));
+    }
+
+    Ok(rule)
+}
+
+#[inline(always)]
+fn parse_frequency(value: &str) -> Frequency {
+    match value.to_ascii_uppercase().as_str() {
+        "SECONDLY" => Frequency::Secondly,
+        "MINUTELY" => Frequency::Minutely,
+        "HOURLY" => Frequency::Hourly,
+        "DAILY" => Frequency::Daily,
+        "WEEKLY" => Frequency::Weekly,
+        "MONTHLY" => Frequency::Monthly,
+        "YEARLY" => Frequency::Yearly,
+        _ => unreachable!("Unexpected frequency: {:?}", value),
+    }
+}
+
+#[inline(always)]
+fn parse_weekday(value: &str) -> Weekday {
+    match value.to_ascii_uppercase().as_str() {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => unreachable!("Unexpected weekday: {:?}", value),
+    }
+}
+
+fn weekday_as_str(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+#[inline(always)]
+fn parse_weekday_num(pair: Pair<Rule>, value: &str) -> Result<WeekdayNum> {
+    let mut weekday_num = WeekdayNum::new(Weekday::Mon);
+    for inner_pair in pair.into_inner() {
+        match inner_pair.as_rule() {
+            Rule::ical_ordinal => {
+                let ordinal = inner_pair
+                    .as_str()
+                    .parse::<i32>()
+                    .map_err(|_| Error::invalid_recurrence(value))?;
+                if ordinal == 0 || ordinal.abs() > 53 {
+                    tracing::trace!("Invalid BYDAY ordinal: {}", inner_pair.as_str());
+                    return Err(Error::invalid_recurrence(value));
+                }
+                weekday_num.ordinal = Some(ordinal);
+            }
+            Rule::ical_weekday => weekday_num.weekday = parse_weekday(inner_pair.as_str()),
+            _ => unreachable!("Unexpected rule in BYDAY parsing: {:?}", inner_pair.as_rule()),
+        }
+    }
+    Ok(weekday_num)
+}
+
+#[inline(always)]
+fn parse_positive(digits: &str, value: &str) -> Result<u32> {
+    match digits.parse::<u32>() {
+        Ok(number) if number > 0 => Ok(number),
+        _ => {
+            tracing::trace!("Expected a positive number: {}", digits);
+            Err(Error::invalid_recurrence(value))
+        }
+    }
+}
+
+#[inline(always)]
+fn parse_unsigned_list(pairs: Pairs<Rule>, range: std::ops::RangeInclusive<u32>, value: &str) -> Result<Vec<u32>> {
+    pairs
+        .map(|pair| match pair.as_str().parse::<u32>() {
+            Ok(number) if range.contains(&number) => Ok(number),
+            _ => {
+                tracing::trace!("Value out of range {:?}: {}", range, pair.as_str());
+                Err(Error::invalid_recurrence(value))
+            }
+        })
+        .collect()
+}
+
+#[inline(always)]
+fn parse_signed_list(pairs: Pairs<Rule>, limit: i32, value: &str) -> Result<Vec<i32>> {
+    pairs
+        .map(|pair| match pair.as_str().parse::<i32>() {
+            Ok(number) if number != 0 && number.abs() <= limit => Ok(number),
+            _ => {
+                tracing::trace!("Value out of range ±{}: {}", limit, pair.as_str());
+                Err(Error::invalid_recurrence(value))
+            }
+        })
+        .collect()
+}
+
+#[inline(always)]
+pub(crate) fn parse_ical_date_value(date_value: &str, end_of_day: bool, value: &str) -> Result<NaiveDateTime> {
+    ICalendarParser::parse(Rule::ical_date_time, date_value).map_err(|_e| Error::invalid_recurrence(value))?;
+
+    let date_value = date_value.trim_end_matches(['Z', 'z']);
+    if date_value.len() == 8 {
+        let date = NaiveDate::parse_from_str(date_value, "%Y%m%d").map_err(|_e| Error::invalid_recurrence(value))?;
+        let time = if end_of_day { NaiveTime::from_hms_opt(23, 59, 59) } else { NaiveTime::from_hms_opt(0, 0, 0) };
+        return Ok(date.and_time(time.expect("time must be valid")));
+    }
+    NaiveDateTime::parse_from_str(&date_value.to_ascii_uppercase(), "%Y%m%dT%H%M%S").map_err(|_e| {
+        tracing::trace!("Invalid date-time value: {}", date_value);
+        Error::invalid_recurrence(value)
+    })
+}
+
+/// A DTSTART, an RRULE and the EXDATEs removed from the occurrences it generates.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecurrenceSet {
+    pub dtstart: NaiveDateTime,
+    pub rule: RecurrenceRule,
+    pub exdates: Vec<NaiveDateTime>,
+}
+
+impl RecurrenceSet {
+    pub fn new(dtstart: NaiveDateTime, rule: RecurrenceRule) -> Self {
+        Self { dtstart, rule, exdates: Vec::new() }
+    }
+
+    pub fn iter(&self) -> Occurrences {
+        Occurrences::new(self.rule.clone(), self.dtstart, self.exdates.clone())
+    }
+}
+
+impl IntoIterator for &RecurrenceSet {
+    type IntoIter = Occurrences;
+    type Item = NaiveDateTime;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl FromStr for RecurrenceSet {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        parse_recurrence_set(value)
+    }
+}
+
+/// Parses `DTSTART`, `RRULE` and `EXDATE` content lines; other properties are ignored.
+///
+/// Property parameters such as `TZID` are ignored, so times are treated as floating local times.
+#[inline(always)]
+pub fn parse_recurrence_set(text: impl AsRef<str>) -> Result<RecurrenceSet> {
+    let text = text.as_ref();
+
+    let mut dtstart = None;
+    let mut rule = None;
+    let mut exdates = Vec::new();
+
+    for line in unfold_lines(text) {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let Some((name, property_value)) = line.split_once(':') else {
+            tracing::trace!("Invalid content line: {}", line);
+            return Err(Error::invalid_recurrence(text));
+        };
+        let name = name
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_uppercase();
+        match name.as_str() {
+            "DTSTART" => dtstart = Some(parse_ical_date_value(property_value, false, text)?),
+            "RRULE" => rule = Some(parse_rrule(property_value)?),
+            "EXDATE" => {
+                for exdate in property_value.split(',') {
+                    exdates.push(parse_ical_date_value(exdate, false, text)?);
+                }
+            }
+            _ => tracing::trace!("Ignoring property: {}", name),
+        }
+    }
+
+    match (dtstart, rule) {
+        (Some(dtstart), Some(rule)) => Ok(RecurrenceSet { dtstart, rule, exdates }),
+        _ => {
+            tracing::trace!("DTSTART and RRULE are required: {}", text);
+            Err(Error::invalid_recurrence(text))
+        }
+    }
+}
+
+pub(crate) fn unfold_lines(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// Iterator over the occurrences of a recurrence rule, in ascending order.
+///
+/// Only instances matching the rule are produced; a DTSTART that does not match is not an occurrence.
+/// `COUNT` counts instances before EXDATEs are removed, as RFC 5545 specifies.
+#[derive(Clone, Debug)]
+pub struct Occurrences {
+    rule: RecurrenceRule,
+    dtstart: NaiveDateTime,
+    exdates: Vec<NaiveDateTime>,
+    period: Option<NaiveDateTime>,
+    pending: VecDeque<NaiveDateTime>,
+    generated: u32,
+}
+
+impl Occurrences {
+    fn new(mut rule: RecurrenceRule, dtstart: NaiveDateTime, exdates: Vec<NaiveDateTime>) -> Self {
+        if rule.by_year_day.is_empty() && rule.by_month_day.is_empty() && rule.by_day.is_empty() {
+            match rule.frequency {
+                Frequency::Yearly => {
+                    if rule.by_month.is_empty() {
+                        rule.by_month = vec![dtstart.month()];
+                    }
+                    rule.by_month_day = vec![dtstart.day() as i32];
+                }
+                Frequency::Monthly => rule.by_month_day = vec![dtstart.day() as i32],
+                Frequency::Weekly => rule.by_day = vec![WeekdayNum::new(dtstart.weekday())],
+                _ => {}
+            }
+        }
+
+        let date = dtstart.date();
+        let period = match rule.frequency {
+            Frequency::Yearly => NaiveDate::from_ymd_opt(date.year(), 1, 1).map(at_midnight),
+            Frequency::Monthly => NaiveDate::from_ymd_opt(date.year(), date.month(), 1).map(at_midnight),
+            Frequency::Weekly => {
+                let offset = (7 + date.weekday().num_days_from_monday() - rule.week_start.num_days_from_monday()) % 7;
+                date.checked_sub_signed(ChronoDuration::days(offset as i64))
+                    .map(at_midnight)
+            }
+            Frequency::Daily => Some(at_midnight(date)),
+            _ => Some(dtstart),
+        };
+
+        Self {
+            rule,
+            dtstart,
+            exdates,
+            period,
+            pending: VecDeque::new(),
+            generated: 0,
+        }
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let rule = &self.rule;
+        if !rule.by_month.is_empty() && !rule.by_month.contains(&date.month()) {
+            return false;
+        }
+        if !rule.by_year_day.is_empty() {
+            let days = days_in_year(date.year()) as i32;
+            let ordinal = date.ordinal() as i32;
+            if !rule
+                .by_year_day
+                .iter()
+                .any(|&day| day == ordinal || days + day + 1 == ordinal)
+            {
+                return false;
+            }
+        }
+        if !rule.by_month_day.is_empty() {
+            let days = days_in_month(date.year(), date.month()) as i32;
+            let day_of_month = date.day() as i32;
+            if !rule
+                .by_month_day
+                .iter()
+                .any(|&day| day == day_of_month || days + day + 1 == day_of_month)
+            {
+                return false;
+            }
+        }
+        if !rule.by_day.is_empty() {
+            // Ordinals count within the month for MONTHLY rules and YEARLY rules limited by BYMONTH, otherwise within the year
+            let within_month = rule.frequency == Frequency::Monthly || !rule.by_month.is_empty();
+            let (position, remaining) = if within_month {
+                (date.day() as i32 - 1, (days_in_month(date.year(), date.month()) - date.day()) as i32)
+            } else {
+                (date.ordinal0() as i32, (days_in_year(date.year()) - date.ordinal()) as i32)
+            };
+            let matches = rule.by_day.iter().any(|day| {
+                day.weekday == date.weekday()
+                    && match day.ordinal {
+                        None => true,
+                        Some(ordinal) if ordinal > 0 => position / 7 + 1 == ordinal,
+                        Some(ordinal) => -(remaining / 7 + 1) == ordinal,
+                    }
+            });
+            if !matches {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn expand_period(&self, period: NaiveDateTime) -> Vec<NaiveDateTime> {
+        let rule = &self.rule;
+        let start = period.date();
+        let days: Vec<NaiveDate> = match rule.frequency {
+            Frequency::Yearly => start
+                .iter_days()
+                .take_while(|date| date.year() == start.year())
+                .collect(),
+            Frequency::Monthly => start
+                .iter_days()
+                .take_while(|date| date.month() == start.month())
+                .collect(),
+            Frequency::Weekly => start.iter_days().take(7).collect(),
+            _ => vec![start],
+        };
+
+        // Components at or below the frequency limit the period's own value, coarser ones expand to their BYxxx list
+        let component = |values: &[u32], frequency: Frequency, current: u32, default: u32| -> Vec<u32> {
+            if rule.frequency <= frequency {
+                if values.is_empty() || values.contains(&current) {
+                    vec![current]
+                } else {
+                    Vec::new()
+                }
+            } else if values.is_empty() {
+                vec![default]
+            } else {
+                let mut values = values.to_vec();
+                values.sort_unstable();
+                values.dedup();
+                values
+            }
+        };
+        let hours = component(&rule.by_hour, Frequency::Hourly, period.hour(), self.dtstart.hour());
+        let minutes = component(&rule.by_minute, Frequency::Minutely, period.minute(), self.dtstart.minute());
+        let seconds = component(&rule.by_second, Frequency::Secondly, period.second(), self.dtstart.second());
+
+        let mut candidates = Vec::new();
+        for date in days
+            .into_iter()
+            .filter(|date| self.matches_day(*date))
+        {
+            for &hour in &hours {
+                for &minute in &minutes {
+                    for &second in &seconds {
+                        if let Some(time) = NaiveTime::from_hms_opt(hour, minute, second) {
+                            candidates.push(date.and_time(time));
+                        }
+                    }
+                }
+            }
+        }
+
+        if rule.by_set_pos.is_empty() {
+            return candidates;
+        }
+        let total = candidates.len() as i32;
+        let mut selected: Vec<NaiveDateTime> = rule
+            .by_set_pos
+            .iter()
+            .filter_map(|&position| {
+                let index = if position > 0 { position - 1 } else { total + position };
+                (0..total)
+                    .contains(&index)
+                    .then(|| candidates[index as usize])
+            })
+            .collect();
+        selected.sort_unstable();
+        selected.dedup();
+        selected
+    }
+
+    fn next_period(&self, period: NaiveDateTime) -> Option<NaiveDateTime> {
+        let interval = self.rule.interval;
+        let step = |seconds: u64| -> Option<NaiveDateTime> {
+            let step = seconds * interval as u64;
+            let mut next = period.checked_add_signed(ChronoDuration::seconds(step as i64))?;
+            // Skip whole days that cannot match instead of stepping through every second of them
+            if !self.matches_day(next.date()) {
+                let remaining = SECONDS_PER_DAY - next.num_seconds_from_midnight() as u64;
+                let steps = remaining.div_ceil(step);
+                next = next.checked_add_signed(ChronoDuration::seconds((steps * step) as i64))?;
+            }
+            Some(next)
+        };
+        let next = match self.rule.frequency {
+            Frequency::Yearly => NaiveDate::from_ymd_opt(period.year() + interval as i32, 1, 1).map(at_midnight),
+            Frequency::Monthly => period.checked_add_months(Months::new(interval)),
+            Frequency::Weekly => period.checked_add_signed(ChronoDuration::weeks(interval as i64)),
+            Frequency::Daily => period.checked_add_signed(ChronoDuration::days(interval as i64)),
+            Frequency::Hourly => step(SECONDS_PER_HOUR),
+            Frequency::Minutely => step(SECONDS_PER_MINUTE),
+            Frequency::Secondly => step(1),
+        };
+        next.filter(|next| next.year() <= MAX_YEAR)
+    }
+}
+
+impl Iterator for Occurrences {
+    type Item = NaiveDateTime;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(occurrence) = self.pending.pop_front() {
+                let exhausted = self
+                    .rule
+                    .count
+                    .is_some_and(|count| self.generated >= count)
+                    || self
+                        .rule
+                        .until
+                        .is_some_and(|until| occurrence > until);
+                if exhausted {
+                    self.pending.clear();
+                    self.period = None;
+                    return None;
+                }
+                self.generated += 1;
+                if self.exdates.contains(&occurrence) {
+                    continue;
+                }
+                return Some(occurrence);
+            }
+
+            let period = self.period?;
+            self.pending.extend(
+                self.expand_period(period)
+                    .into_iter()
+                    .filter(|occurrence| *occurrence >= self.dtstart),
+            );
+            self.period = self.next_period(period);
+        }
+    }
+}
+
+fn at_midnight(date: NaiveDate) -> NaiveDateTime {
+    date.and_time(NaiveTime::MIN)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    fn datetime(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").expect("datetime must be valid for this test")
+    }
+
+    #[rstest]
+    #[case::valid_week("P1W", Ok(ChronoDuration::weeks(1)))]
+    #[case::valid_negative_minutes("-PT15M", Ok(-ChronoDuration::minutes(15)))]
+    #[case::valid_positive_sign("+PT1H", Ok(ChronoDuration::hours(1)))]
+    #[case::valid_day_and_time("P15DT5H0M20S", Ok(ChronoDuration::seconds((15 * SECONDS_PER_DAY + 5 * SECONDS_PER_HOUR + 20) as i64)))]
+    #[case::valid_day("P7D", Ok(ChronoDuration::days(7)))]
+    #[case::valid_seconds("PT25S", Ok(ChronoDuration::seconds(25)))]
+    #[case::valid_lowercase("pt1h30m", Ok(ChronoDuration::minutes(90)))]
+    #[case::invalid_week_and_day("P1W2D", Err(Error::invalid_duration("P1W2D")))]
+    #[case::invalid_hour_then_second("PT1H5S", Err(Error::invalid_duration("PT1H5S")))]
+    #[case::invalid_empty_time("PT", Err(Error::invalid_duration("PT")))]
+    #[case::invalid_years("P1Y", Err(Error::invalid_duration("P1Y")))]
+    #[case::invalid_shorthand("1h30m", Err(Error::invalid_duration("1h30m")))]
+    fn test_parse_ical_duration(#[case] value: &str, #[case] expected: Result<ChronoDuration>) {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .try_init()
+            .ok();
+
+        let result = parse_ical_duration(value);
+        match expected {
+            Ok(expected) => match result {
+                Ok(result) => assert_eq!(result, expected, "expected: {:?}, actual: {:?}", expected, result),
+                Err(e) => panic!("Expected Ok, got Err: {:?}", e),
+            },
+            Err(e) => match result {
+                Ok(actual) => panic!("Expected Err, got Ok {:?} - {:?}", actual, e),
+                Err(result) => assert_eq!(result.to_string(), e.to_string()),
+            },
+        }
+    }
+
+    #[rstest]
+    #[case::week(ChronoDuration::weeks(1), "P1W")]
+    #[case::negative_minutes(-ChronoDuration::minutes(15), "-PT15M")]
+    #[case::zero(ChronoDuration::zero(), "PT0S")]
+    #[case::days(ChronoDuration::days(8), "P8D")]
+    #[case::day_and_hours(ChronoDuration::hours(26), "P1DT2H")]
+    #[case::hours_and_seconds(ChronoDuration::seconds((SECONDS_PER_HOUR + 5) as i64), "PT1H0M5S")]
+    #[case::minutes_and_seconds(ChronoDuration::seconds(90), "PT1M30S")]
+    #[case::subsecond_truncated(ChronoDuration::milliseconds(1500), "PT1S")]
+    fn test_ical_duration_as_string(#[case] duration: ChronoDuration, #[case] expected: &str) {
+        let result = ical_duration_as_string(&duration);
+        assert_eq!(result, expected);
+        assert_eq!(parse_ical_duration(&result).expect("formatted value must parse"), ChronoDuration::seconds(duration.num_seconds()));
+    }
+
+    #[rstest]
+    #[case::weekly("FREQ=WEEKLY;BYDAY=MO,WE;COUNT=10", "FREQ=WEEKLY;COUNT=10;BYDAY=MO,WE")]
+    #[case::property_prefix("RRULE:FREQ=DAILY;INTERVAL=2", "FREQ=DAILY;INTERVAL=2")]
+    #[case::any_order("COUNT=3;FREQ=MONTHLY;BYDAY=-1FR", "FREQ=MONTHLY;COUNT=3;BYDAY=-1FR")]
+    #[case::until_date_only("FREQ=DAILY;UNTIL=20240105", "FREQ=DAILY;UNTIL=20240105T235959")]
+    #[case::until_utc("FREQ=DAILY;UNTIL=20240105T090000Z", "FREQ=DAILY;UNTIL=20240105T090000")]
+    #[case::lowercase("freq=yearly;bymonth=1,7;wkst=su", "FREQ=YEARLY;BYMONTH=1,7;WKST=SU")]
+    fn test_parse_rrule(#[case] value: &str, #[case] expected: &str) {
+        let rule = parse_rrule(value).expect("rrule must be valid for this test");
+        assert_eq!(rule.to_string(), expected);
+        assert_eq!(parse_rrule(expected).expect("formatted rrule must parse"), rule);
+    }
+
+    #[rstest]
+    #[case::missing_freq("COUNT=10")]
+    #[case::repeated_part("FREQ=DAILY;FREQ=WEEKLY")]
+    #[case::count_and_until("FREQ=DAILY;COUNT=2;UNTIL=20240101")]
+    #[case::zero_interval("FREQ=DAILY;INTERVAL=0")]
+    #[case::ordinal_with_weekly("FREQ=WEEKLY;BYDAY=1MO")]
+    #[case::month_out_of_range("FREQ=YEARLY;BYMONTH=13")]
+    #[case::zero_month_day("FREQ=MONTHLY;BYMONTHDAY=0")]
+    #[case::unknown_part("FREQ=DAILY;BYEASTER=1")]
+    #[case::unsupported_byweekno("FREQ=YEARLY;BYWEEKNO=20")]
+    fn test_parse_rrule_invalid(#[case] value: &str) {
+        let result = parse_rrule(value);
+        match result {
+            Ok(actual) => panic!("Expected Err, got Ok {:?}", actual),
+            Err(e) => assert_eq!(e.to_string(), Error::invalid_recurrence(value).to_string()),
+        }
+    }
+
+    #[rstest]
+    #[case::weekly_mon_wed(
+        "2024-01-01 09:00:00",
+        "FREQ=WEEKLY;BYDAY=MO,WE;COUNT=5",
+        &["2024-01-01 09:00:00", "2024-01-03 09:00:00", "2024-01-08 09:00:00", "2024-01-10 09:00:00", "2024-01-15 09:00:00"]
+    )]
+    #[case::daily_until("2024-01-30 08:30:00", "FREQ=DAILY;UNTIL=20240202T083000", &["2024-01-30 08:30:00", "2024-01-31 08:30:00", "2024-02-01 08:30:00", "2024-02-02 08:30:00"])]
+    #[case::monthly_last_friday("2024-01-01 12:00:00", "FREQ=MONTHLY;BYDAY=-1FR;COUNT=3", &["2024-01-26 12:00:00", "2024-02-23 12:00:00", "2024-03-29 12:00:00"])]
+    #[case::monthly_day_31_skips_short_months("2024-01-31 00:00:00", "FREQ=MONTHLY;COUNT=3", &["2024-01-31 00:00:00", "2024-03-31 00:00:00", "2024-05-31 00:00:00"])]
+    #[case::monthly_last_weekday("2024-01-01 17:00:00", "FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1;COUNT=3", &["2024-01-31 17:00:00", "2024-02-29 17:00:00", "2024-03-29 17:00:00"])]
+    #[case::yearly_leap_day("2024-02-29 00:00:00", "FREQ=YEARLY;COUNT=2", &["2024-02-29 00:00:00", "2028-02-29 00:00:00"])]
+    #[case::yearly_thanksgiving("2024-01-01 00:00:00", "FREQ=YEARLY;BYMONTH=11;BYDAY=4TH;COUNT=2", &["2024-11-28 00:00:00", "2025-11-27 00:00:00"])]
+    #[case::yearly_day_of_year("2024-01-01 00:00:00", "FREQ=YEARLY;BYYEARDAY=1,-1;COUNT=3", &["2024-01-01 00:00:00", "2024-12-31 00:00:00", "2025-01-01 00:00:00"])]
+    #[case::biweekly("2024-01-02 10:00:00", "FREQ=WEEKLY;INTERVAL=2;COUNT=3", &["2024-01-02 10:00:00", "2024-01-16 10:00:00", "2024-01-30 10:00:00"])]
+    #[case::daily_by_hour("2024-01-01 09:00:00", "FREQ=DAILY;BYHOUR=9,17;BYMINUTE=0,30;COUNT=5", &["2024-01-01 09:00:00", "2024-01-01 09:30:00", "2024-01-01 17:00:00", "2024-01-01 17:30:00", "2024-01-02 09:00:00"])]
+    #[case::hourly_limited("2024-01-01 22:15:00", "FREQ=HOURLY;INTERVAL=3;BYHOUR=1,4;COUNT=3", &["2024-01-02 01:15:00", "2024-01-02 04:15:00", "2024-01-03 01:15:00"])]
+    #[case::minutely_weekdays("2024-01-05 23:58:00", "FREQ=MINUTELY;BYDAY=FR,MO;COUNT=3", &["2024-01-05 23:58:00", "2024-01-05 23:59:00", "2024-01-08 00:00:00"])]
+    #[case::dtstart_not_matching("2024-01-02 09:00:00", "FREQ=WEEKLY;BYDAY=MO;COUNT=2", &["2024-01-08 09:00:00", "2024-01-15 09:00:00"])]
+    #[case::impossible_date("2024-01-01 00:00:00", "FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30", &[])]
+    fn test_rrule_occurrences(#[case] dtstart: &str, #[case] rrule: &str, #[case] expected: &[&str]) {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::ERROR)
+            .try_init()
+            .ok();
+
+        let rule = parse_rrule(rrule).expect("rrule must be valid for this test");
+        let result: Vec<NaiveDateTime> = rule
+            .occurrences(datetime(dtstart))
+            .take(expected.len() + 1)
+            .collect();
+        let expected: Vec<NaiveDateTime> = expected
+            .iter()
+            .map(|value| datetime(value))
+            .collect();
+        assert_eq!(result, expected);
+    }
+
+    #[rstest]
+    #[case::exdate_counts_toward_count(
+        "DTSTART:20240101T090000\nRRULE:FREQ=WEEKLY;BYDAY=MO,WE;COUNT=4\nEXDATE:20240103T090000,20240108T090000",
+        &["2024-01-01 09:00:00", "2024-01-10 09:00:00"]
+    )]
+    #[case::tzid_and_folded_lines(
+        "BEGIN:VEVENT\nDTSTART;TZID=Europe/Berlin:20240105T080000\nRRULE:FREQ=DAILY;\n COUNT=3\nSUMMARY:standup\nEND:VEVENT",
+        &["2024-01-05 08:00:00", "2024-01-06 08:00:00", "2024-01-07 08:00:00"]
+    )]
+    #[case::date_only_dtstart("DTSTART;VALUE=DATE:20240101\nRRULE:FREQ=YEARLY;COUNT=2", &["2024-01-01 00:00:00", "2025-01-01 00:00:00"])]
+    fn test_parse_recurrence_set(#[case] text: &str, #[case] expected: &[&str]) {
+        let recurrence_set = parse_recurrence_set(text).expect("recurrence set must be valid for this test");
+        let result: Vec<NaiveDateTime> = recurrence_set.iter().collect();
+        let expected: Vec<NaiveDateTime> = expected
+            .iter()
+            .map(|value| datetime(value))
+            .collect();
+        assert_eq!(result, expected);
+    }
+
+    #[rstest]
+    #[case::missing_rrule("DTSTART:20240101T090000")]
+    #[case::missing_dtstart("RRULE:FREQ=DAILY")]
+    #[case::invalid_dtstart("DTSTART:2024-01-01\nRRULE:FREQ=DAILY")]
+    fn test_parse_recurrence_set_invalid(#[case] text: &str) {
+        assert!(parse_recurrence_set(text).is_err());
+    }
+}
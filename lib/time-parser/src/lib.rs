@@ -1,11 +1,58 @@
+mod business;
+mod calendar;
+mod calendar_events;
+mod clocks;
 mod constants;
+mod constraints;
+mod conversions;
+mod datetimes;
+mod deadlines;
+mod digit_grouping;
 mod duration_formatter;
+mod duration_parser;
+mod duration_parts;
 mod durations;
+mod epoch;
 mod error;
+mod expressions;
+mod human_duration;
+mod icalendar;
+mod periods;
+mod ranges;
+mod relative;
+mod schedules;
+mod time_of_day;
+mod timezones;
+mod units;
 
+pub use business::WorkCalendar;
+pub use calendar::{calendar_difference, CalendarDuration};
+pub use calendar_events::{parse_calendar_event, CalendarEvent, Elapses};
+pub use clocks::{Clock, FixedClock, SystemClock};
 pub use constants::{NANOSECONDS_PER_MICROSECOND, NANOSECONDS_PER_MILLISECOND, SECONDS_PER_DAY, SECONDS_PER_HOUR, SECONDS_PER_MINUTE, SECONDS_PER_WEEK};
+pub use constraints::DurationConstraint;
+pub use conversions::{convert_duration, Conversion};
+pub use datetimes::{
+    ordinal_date_as_string, parse_datetime, parse_datetime_with_abbreviations, parse_datetime_with_clock, parse_ordinal_date, parse_week_date, week_date_as_string, DateTimeFormat, IsoDateStyle,
+    ParsedDateTime,
+};
+pub use deadlines::{parse_deadline, parse_deadline_from, Deadline};
+pub use digit_grouping::DigitGrouping;
 pub use duration_formatter::DurationFormatter;
-pub use durations::{duration_as_string, parse_duration, parse_duration_hms, parse_duration_shorthand, parse_durations};
-pub use error::Error;
+pub use duration_parser::{DurationParser, ParserOptions};
+pub use duration_parts::{parse_duration_parts, DurationComponent, DurationParts, DurationStyle};
+pub use durations::{duration_as_string, parse_duration, parse_duration_bounded, parse_duration_hms, parse_duration_shorthand, parse_durations};
+pub use epoch::{epoch_as_string, parse_epoch, parse_epoch_as, EpochUnit};
+pub use error::{Error, StrictViolation};
+pub use expressions::eval_duration_expr;
+pub use human_duration::HumanDuration;
+pub use icalendar::{ical_duration_as_string, parse_ical_duration, parse_recurrence_set, parse_rrule, Frequency, Occurrences, RecurrenceRule, RecurrenceSet, WeekdayNum};
+pub use periods::{parse_period, Period, PeriodOptions};
+pub use ranges::{parse_interval, Interval};
+pub use relative::parse_relative_datetime;
+pub use schedules::{parse_schedule, FireTimes, Schedule};
+pub use time_of_day::parse_time_of_day;
+pub use timezones::{parse_timezone, parse_zoned_datetime, parse_zoned_time_of_day, TimeZoneAbbreviations, Zone};
+pub use units::{DurationUnits, Unit};
 
 pub type Result<T> = std::result::Result<T, Error>;
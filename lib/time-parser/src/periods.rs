@@ -0,0 +1,483 @@
+use std::fmt;
+
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, FixedOffset, NaiveDate, Weekday};
+use pest::iterators::Pair;
+use pest::Parser;
+use pest_derive::Parser;
+
+use crate::{Error, Result, Zone};
+
+#[derive(Parser)]
+#[grammar = "grammars/datetimes.pest"]
+#[grammar = "grammars/periods.pest"]
+struct PeriodParser;
+
+/// How named periods map onto the calendar.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PeriodOptions {
+    /// The first day of a week.
+    pub week_start: Weekday,
+    /// The month (1-12) a fiscal year starts in.  Fiscal years are named after the calendar year they end in, so with
+    /// a July start `FY25` runs from 2024-07-01 to 2025-07-01.
+    pub fiscal_year_start: u32,
+}
+
+impl Default for PeriodOptions {
+    fn default() -> Self {
+        Self {
+            week_start: Weekday::Mon,
+            fiscal_year_start: 1,
+        }
+    }
+}
+
+/// A named calendar period, such as a day, a week or a fiscal quarter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Period {
+    Day(NaiveDate),
+    Week { start: NaiveDate },
+    Month { year: i32, month: u32 },
+    Quarter { year: i32, quarter: u32 },
+    Year(i32),
+    FiscalQuarter { year: i32, quarter: u32, start_month: u32 },
+    FiscalYear { year: i32, start_month: u32 },
+}
+
+impl Period {
+    /// The half-open `[start, end)` range of dates the period covers.
+    ///
+    /// Returns `None` for periods that do not exist, such as month 13 or quarter 0, and for periods whose end is past
+    /// the last representable date.
+    pub fn dates(&self) -> Option<(NaiveDate, NaiveDate)> {
+        match *self {
+            Self::Day(date) => Some((date, date.succ_opt()?)),
+            Self::Week { start } => Some((start, start.checked_add_signed(ChronoDuration::weeks(1))?)),
+            Self::Month { year, month } => month_range(year, month, 1),
+            Self::Quarter { year, quarter } => month_range(year, quarter_start_month(quarter)?, 3),
+            Self::Year(year) => month_range(year, 1, 12),
+            Self::FiscalQuarter { year, quarter, start_month } => {
+                let (start_year, start_month) = fiscal_year_start(year, start_month)?;
+                let (year, month) = add_months(start_year, start_month, quarter_start_month(quarter)? as i32 - 1)?;
+                month_range(year, month, 3)
+            }
+            Self::FiscalYear { year, start_month } => {
+                let (start_year, start_month) = fiscal_year_start(year, start_month)?;
+                month_range(start_year, start_month, 12)
+            }
+        }
+    }
+
+    /// The half-open `[start, end)` range of the period from the start of its first day to the start of the day after
+    /// it in `zone`.  A day whose midnight is skipped by DST starts when the gap ends.
+    ///
+    /// Returns `None` when the period does not exist.
+    pub fn range(&self, zone: &Zone) -> Option<(DateTime<FixedOffset>, DateTime<FixedOffset>)> {
+        let (start, end) = self.dates()?;
+        Some((zone.start_of_day(start)?, zone.start_of_day(end)?))
+    }
+}
+
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Day(date) => write!(f, "{}", date.format("%Y-%m-%d")),
+            Self::Week { start } => write!(f, "week of {}", start.format("%Y-%m-%d")),
+            Self::Month { year, month } => write!(f, "{:04}-{:02}", year, month),
+            Self::Quarter { year, quarter } => write!(f, "Q{} {:04}", quarter, year),
+            Self::Year(year) => write!(f, "{:04}", year),
+            Self::FiscalQuarter { year, quarter, .. } => write!(f, "Q{} FY{:04}", quarter, year),
+            Self::FiscalYear { year, .. } => write!(f, "FY{:04}", year),
+        }
+    }
+}
+
+/// Parses a named period, resolving relative names against `reference`.
+///
+/// Supported names:
+/// - `today`, `yesterday`, `tomorrow`
+/// - `this week`, `last month`, `next quarter`, `this year`, `last fiscal year`, `this fiscal quarter`
+/// - `2024-10-18`, `week of 2024-10-14`, `2024-10`, `October 2024`, `Q3 2024`, `2024-Q3`, `2024`
+/// - `FY25`, `FY2025`, `Q1 FY25`
+///
+/// Months and quarters without a year fall in the reference year.  Periods format back to the canonical name that
+/// parses to the same period, such as `Q3 2024` or `FY2025`.
+#[inline(always)]
+pub fn parse_period(input: impl AsRef<str>, reference: NaiveDate, options: &PeriodOptions) -> Result<Period> {
+    let input = input.as_ref();
+    if !(1..=12).contains(&options.fiscal_year_start) {
+        tracing::trace!("Invalid fiscal year start month: {}", options.fiscal_year_start);
+        return Err(Error::invalid_time(input));
+    }
+    let parse_result = PeriodParser::parse(Rule::period, input.trim()).map_err(|_e| Error::invalid_time(input))?;
+
+    let Some(pair) = parse_result
+        .flat_map(|pair| pair.into_inner())
+        .next()
+    else {
+        unreachable!("Period must have a form");
+    };
+
+    let period = match pair.as_rule() {
+        Rule::period_today => Some(Period::Day(reference)),
+        Rule::period_yesterday => reference.pred_opt().map(Period::Day),
+        Rule::period_tomorrow => reference.succ_opt().map(Period::Day),
+        Rule::period_relative => parse_relative(pair, reference, options),
+        Rule::period_week_of => pair
+            .into_inner()
+            .next()
+            .and_then(|date| parse_date(date, input).ok())
+            .map(|date| Period::Week {
+                start: week_start(date, options.week_start),
+            }),
+        Rule::period_date => Some(Period::Day(parse_date(pair, input)?)),
+        Rule::period_year_month | Rule::period_month_of_year => {
+            let (mut year, mut month) = (reference.year(), 0);
+            for inner_pair in pair.into_inner() {
+                match inner_pair.as_rule() {
+                    Rule::dt_year => year = number(&inner_pair, input)? as i32,
+                    Rule::dt_month => month = number(&inner_pair, input)?,
+                    rule => month = month_from_rule(rule),
+                }
+            }
+            (1..=12)
+                .contains(&month)
+                .then_some(Period::Month { year, month })
+        }
+        Rule::period_quarter_of_year | Rule::period_calendar_year => {
+            let (mut year, mut quarter) = (reference.year(), None);
+            for inner_pair in pair.into_inner() {
+                match inner_pair.as_rule() {
+                    Rule::dt_year => year = number(&inner_pair, input)? as i32,
+                    Rule::period_quarter => quarter = Some(parse_quarter(inner_pair, input)?),
+                    _ => unreachable!("Unexpected rule in quarter parsing: {:?}", inner_pair.as_rule()),
+                }
+            }
+            match quarter {
+                Some(quarter) => Some(Period::Quarter { year, quarter }),
+                None => Some(Period::Year(year)),
+            }
+        }
+        Rule::period_fiscal_year => Some(Period::FiscalYear {
+            year: parse_fiscal_year(pair, input)?,
+            start_month: options.fiscal_year_start,
+        }),
+        Rule::period_fiscal_quarter => {
+            let (mut year, mut quarter) = (0, 0);
+            for inner_pair in pair.into_inner() {
+                match inner_pair.as_rule() {
+                    Rule::period_fiscal_year => year = parse_fiscal_year(inner_pair, input)?,
+                    Rule::period_quarter => quarter = parse_quarter(inner_pair, input)?,
+                    _ => unreachable!("Unexpected rule in fiscal quarter parsing: {:?}", inner_pair.as_rule()),
+                }
+            }
+            Some(Period::FiscalQuarter {
+                year,
+                quarter,
+                start_month: options.fiscal_year_start,
+            })
+        }
+        _ => unreachable!("Unexpected rule in period parsing: {:?}", pair.as_rule()),
+    };
+
+    period
+        .filter(|period| period.dates().is_some())
+        .ok_or_else(|| {
+            tracing::trace!("Invalid period: {}", input);
+            Error::invalid_time(input)
+        })
+}
+
+#[inline(always)]
+fn parse_relative(pair: Pair<Rule>, reference: NaiveDate, options: &PeriodOptions) -> Option<Period> {
+    let mut inner_pairs = pair.into_inner();
+    let offset = match inner_pairs.next().map(|pair| pair.as_rule()) {
+        Some(Rule::period_last) => -1,
+        Some(Rule::period_next) => 1,
+        _ => 0,
+    };
+    let unit = inner_pairs.next().map(|pair| pair.as_rule());
+
+    let (year, month) = (reference.year(), reference.month());
+    let period = match unit {
+        Some(Rule::period_week) => Period::Week {
+            start: week_start(reference, options.week_start).checked_add_signed(ChronoDuration::weeks(offset as i64))?,
+        },
+        Some(Rule::period_month) => {
+            let (year, month) = add_months(year, month, offset)?;
+            Period::Month { year, month }
+        }
+        Some(Rule::period_quarter_unit) => {
+            let (year, month) = add_months(year, (month - 1) / 3 * 3 + 1, offset * 3)?;
+            Period::Quarter { year, quarter: (month - 1) / 3 + 1 }
+        }
+        Some(Rule::period_year_unit) => Period::Year(year + offset),
+        Some(Rule::period_fiscal_year_unit) => Period::FiscalYear {
+            year: fiscal_year_of(reference, options.fiscal_year_start) + offset,
+            start_month: options.fiscal_year_start,
+        },
+        Some(Rule::period_fiscal_quarter_unit) => {
+            let fiscal_month = (month + 12 - options.fiscal_year_start) % 12;
+            let quarter_index = fiscal_year_of(reference, options.fiscal_year_start) * 4 + (fiscal_month / 3) as i32 + offset;
+            Period::FiscalQuarter {
+                year: quarter_index.div_euclid(4),
+                quarter: quarter_index.rem_euclid(4) as u32 + 1,
+                start_month: options.fiscal_year_start,
+            }
+        }
+        _ => unreachable!("Unexpected unit in relative period parsing: {:?}", unit),
+    };
+    Some(period)
+}
+
+#[inline(always)]
+fn parse_date(pair: Pair<Rule>, input: &str) -> Result<NaiveDate> {
+    let (mut year, mut month, mut day) = (0, 0, 0);
+    for inner_pair in pair.into_inner() {
+        match inner_pair.as_rule() {
+            Rule::dt_year => year = number(&inner_pair, input)? as i32,
+            Rule::dt_month => month = number(&inner_pair, input)?,
+            Rule::dt_day => day = number(&inner_pair, input)?,
+            _ => unreachable!("Unexpected rule in date parsing: {:?}", inner_pair.as_rule()),
+        }
+    }
+    NaiveDate::from_ymd_opt(year, month, day).ok_or_else(|| Error::invalid_time(input))
+}
+
+#[inline(always)]
+fn parse_quarter(pair: Pair<Rule>, input: &str) -> Result<u32> {
+    match pair.into_inner().next() {
+        Some(number_pair) => number(&number_pair, input),
+        None => unreachable!("Quarter must have a number"),
+    }
+}
+
+/// Two-digit fiscal years are in the 2000s.
+#[inline(always)]
+fn parse_fiscal_year(pair: Pair<Rule>, input: &str) -> Result<i32> {
+    let Some(digits) = pair.into_inner().next() else {
+        unreachable!("Fiscal year must have digits");
+    };
+    let year = number(&digits, input)? as i32;
+    Ok(if digits.as_str().len() == 2 { 2000 + year } else { year })
+}
+
+#[inline(always)]
+fn number(pair: &Pair<Rule>, input: &str) -> Result<u32> {
+    pair.as_str()
+        .parse::<u32>()
+        .map_err(|_e| Error::invalid_time(input))
+}
+
+#[inline(always)]
+fn month_from_rule(rule: Rule) -> u32 {
+    match rule {
+        Rule::dt_january => 1,
+        Rule::dt_february => 2,
+        Rule::dt_march => 3,
+        Rule::dt_april => 4,
+        Rule::dt_may => 5,
+        Rule::dt_june => 6,
+        Rule::dt_july => 7,
+        Rule::dt_august => 8,
+        Rule::dt_september => 9,
+        Rule::dt_october => 10,
+        Rule::dt_november => 11,
+        Rule::dt_december => 12,
+        _ => unreachable!("Unexpected rule in month parsing: {:?}", rule),
+    }
+}
+
+#[inline(always)]
+fn week_start(date: NaiveDate, start: Weekday) -> NaiveDate {
+    let days_back = (7 + date.weekday().num_days_from_monday() - start.num_days_from_monday()) % 7;
+    date - ChronoDuration::days(days_back as i64)
+}
+
+/// The fiscal year `date` falls in.
+#[inline(always)]
+fn fiscal_year_of(date: NaiveDate, start_month: u32) -> i32 {
+    if start_month > 1 && date.month() >= start_month {
+        date.year() + 1
+    } else {
+        date.year()
+    }
+}
+
+/// The calendar year and month a fiscal year starts in.
+#[inline(always)]
+fn fiscal_year_start(year: i32, start_month: u32) -> Option<(i32, u32)> {
+    match start_month {
+        1 => Some((year, 1)),
+        2..=12 => Some((year.checked_sub(1)?, start_month)),
+        _ => None,
+    }
+}
+
+/// The first month of a quarter, counting from 1.
+#[inline(always)]
+fn quarter_start_month(quarter: u32) -> Option<u32> {
+    (1..=4)
+        .contains(&quarter)
+        .then(|| (quarter - 1) * 3 + 1)
+}
+
+#[inline(always)]
+fn add_months(year: i32, month: u32, months: i32) -> Option<(i32, u32)> {
+    let index = (year as i64) * 12 + month as i64 - 1 + months as i64;
+    Some((i32::try_from(index.div_euclid(12)).ok()?, index.rem_euclid(12) as u32 + 1))
+}
+
+#[inline(always)]
+fn month_range(year: i32, month: u32, months: i32) -> Option<(NaiveDate, NaiveDate)> {
+    let (end_year, end_month) = add_months(year, month, months)?;
+    let start = NaiveDate::from_ymd_opt(year, month, 1)?;
+    let end = NaiveDate::from_ymd_opt(end_year, end_month, 1)?;
+    Some((start, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono_tz::Tz;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").expect("date must be valid for this test")
+    }
+
+    // 2024-10-18 is a Friday
+    const REFERENCE: &str = "2024-10-18";
+
+    #[rstest]
+    #[case::today("today", "2024-10-18", "2024-10-19", "2024-10-18")]
+    #[case::yesterday("Yesterday", "2024-10-17", "2024-10-18", "2024-10-17")]
+    #[case::tomorrow("tomorrow", "2024-10-19", "2024-10-20", "2024-10-19")]
+    #[case::this_week("this week", "2024-10-14", "2024-10-21", "week of 2024-10-14")]
+    #[case::last_week("last week", "2024-10-07", "2024-10-14", "week of 2024-10-07")]
+    #[case::next_week("next  week", "2024-10-21", "2024-10-28", "week of 2024-10-21")]
+    #[case::this_month("this month", "2024-10-01", "2024-11-01", "2024-10")]
+    #[case::last_month("previous month", "2024-09-01", "2024-10-01", "2024-09")]
+    #[case::next_month("next month", "2024-11-01", "2024-12-01", "2024-11")]
+    #[case::this_quarter("this quarter", "2024-10-01", "2025-01-01", "Q4 2024")]
+    #[case::next_quarter("next quarter", "2025-01-01", "2025-04-01", "Q1 2025")]
+    #[case::last_quarter("last quarter", "2024-07-01", "2024-10-01", "Q3 2024")]
+    #[case::this_year("this year", "2024-01-01", "2025-01-01", "2024")]
+    #[case::last_year("last year", "2023-01-01", "2024-01-01", "2023")]
+    #[case::this_fiscal_year("this fiscal year", "2024-01-01", "2025-01-01", "FY2024")]
+    #[case::day("2024-02-29", "2024-02-29", "2024-03-01", "2024-02-29")]
+    #[case::week_of("week of 2024-10-16", "2024-10-14", "2024-10-21", "week of 2024-10-14")]
+    #[case::year_month("2024-02", "2024-02-01", "2024-03-01", "2024-02")]
+    #[case::month_name("October 2024", "2024-10-01", "2024-11-01", "2024-10")]
+    #[case::month_name_without_year("feb", "2024-02-01", "2024-03-01", "2024-02")]
+    #[case::quarter("Q3 2024", "2024-07-01", "2024-10-01", "Q3 2024")]
+    #[case::quarter_year_first("2024-Q3", "2024-07-01", "2024-10-01", "Q3 2024")]
+    #[case::quarter_without_year("q1", "2024-01-01", "2024-04-01", "Q1 2024")]
+    #[case::year("2023", "2023-01-01", "2024-01-01", "2023")]
+    #[case::fiscal_year("FY25", "2025-01-01", "2026-01-01", "FY2025")]
+    #[case::fiscal_quarter("Q2 FY2025", "2025-04-01", "2025-07-01", "Q2 FY2025")]
+    fn test_parse_period(#[case] input: &str, #[case] start: &str, #[case] end: &str, #[case] name: &str) {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .try_init()
+            .ok();
+
+        let options = PeriodOptions::default();
+        match parse_period(input, date(REFERENCE), &options) {
+            Ok(period) => {
+                assert_eq!(period.dates(), Some((date(start), date(end))));
+                assert_eq!(period.to_string(), name);
+                assert_eq!(parse_period(name, date(REFERENCE), &options).ok(), Some(period));
+            }
+            Err(e) => panic!("Expected Ok, got Err: {:?}", e),
+        }
+    }
+
+    #[rstest]
+    #[case::fiscal_year("FY25", "2024-07-01", "2025-07-01")]
+    #[case::this_fiscal_year("this fiscal year", "2024-07-01", "2025-07-01")]
+    #[case::last_fiscal_year("last FY", "2023-07-01", "2024-07-01")]
+    #[case::fiscal_quarter("Q1 FY25", "2024-07-01", "2024-10-01")]
+    #[case::fiscal_quarter_year_first("FY2025 Q4", "2025-04-01", "2025-07-01")]
+    #[case::this_fiscal_quarter("this fiscal quarter", "2024-10-01", "2025-01-01")]
+    #[case::last_fiscal_quarter("last fiscal quarter", "2024-07-01", "2024-10-01")]
+    #[case::sunday_week("this week", "2024-10-13", "2024-10-20")]
+    fn test_parse_period_with_options(#[case] input: &str, #[case] start: &str, #[case] end: &str) {
+        let options = PeriodOptions {
+            week_start: Weekday::Sun,
+            fiscal_year_start: 7,
+        };
+        let period = parse_period(input, date(REFERENCE), &options).expect("period must be valid for this test");
+        assert_eq!(period.dates(), Some((date(start), date(end))));
+        assert_eq!(parse_period(period.to_string(), date(REFERENCE), &options).ok(), Some(period));
+    }
+
+    #[test]
+    fn test_period_range_in_zone() {
+        let period = parse_period("2024-03", date(REFERENCE), &PeriodOptions::default()).expect("period must be valid for this test");
+        let (start, end) = period
+            .range(&Zone::Named(Tz::Europe__Berlin))
+            .expect("range must exist for this test");
+        assert_eq!(start.to_rfc3339(), "2024-03-01T00:00:00+01:00");
+        assert_eq!(end.to_rfc3339(), "2024-04-01T00:00:00+02:00");
+    }
+
+    #[test]
+    fn test_range_midnight_dst_gap() {
+        // Brazil started DST at midnight on 2018-11-04, so that day began at 01:00.
+        let (start, end) = parse_period("today", date("2018-11-04"), &PeriodOptions::default())
+            .expect("period must be valid for this test")
+            .range(&Zone::Named(Tz::America__Sao_Paulo))
+            .expect("range must exist for this test");
+        assert_eq!(start.to_rfc3339(), "2018-11-04T01:00:00-02:00");
+        assert_eq!(end.to_rfc3339(), "2018-11-05T00:00:00-02:00");
+
+        let (_, end) = parse_period("yesterday", date("2018-11-04"), &PeriodOptions::default())
+            .expect("period must be valid for this test")
+            .range(&Zone::Named(Tz::America__Sao_Paulo))
+            .expect("range must exist for this test");
+        assert_eq!(end, start);
+    }
+
+    #[rstest]
+    #[case::empty("")]
+    #[case::unknown("someday")]
+    #[case::quarter_out_of_range("Q5 2024")]
+    #[case::month_out_of_range("2024-13")]
+    #[case::invalid_date("2024-02-30")]
+    #[case::three_digit_fiscal_year("FY202")]
+    #[case::relative_day("next day")]
+    #[case::trailing_text("this week please")]
+    fn test_parse_period_invalid(#[case] input: &str) {
+        match parse_period(input, date(REFERENCE), &PeriodOptions::default()) {
+            Ok(actual) => panic!("Expected Err, got Ok {:?}", actual),
+            Err(e) => assert_eq!(e.to_string(), Error::invalid_time(input).to_string()),
+        }
+    }
+
+    #[test]
+    fn test_parse_period_invalid_fiscal_start() {
+        let options = PeriodOptions {
+            fiscal_year_start: 13,
+            ..PeriodOptions::default()
+        };
+        assert!(parse_period("FY25", date(REFERENCE), &options).is_err());
+    }
+
+    #[rstest]
+    #[case::quarter_zero(Period::Quarter { year: 2024, quarter: 0 })]
+    #[case::quarter_five(Period::Quarter { year: 2024, quarter: 5 })]
+    #[case::month_zero(Period::Month { year: 2024, month: 0 })]
+    #[case::month_thirteen(Period::Month { year: 2024, month: 13 })]
+    #[case::last_day(Period::Day(NaiveDate::MAX))]
+    #[case::last_week(Period::Week { start: NaiveDate::MAX })]
+    #[case::year_out_of_range(Period::Year(i32::MAX))]
+    #[case::fiscal_quarter_zero(Period::FiscalQuarter { year: 2024, quarter: 0, start_month: 7 })]
+    #[case::fiscal_start_month_zero(Period::FiscalYear { year: 2024, start_month: 0 })]
+    #[case::fiscal_start_month_thirteen(Period::FiscalQuarter { year: 2024, quarter: 1, start_month: 13 })]
+    #[case::fiscal_year_underflow(Period::FiscalYear { year: i32::MIN, start_month: 7 })]
+    fn test_dates_invalid(#[case] period: Period) {
+        assert_eq!(period.dates(), None);
+        assert_eq!(period.range(&Zone::Offset(FixedOffset::east_opt(0).expect("offset must be valid"))), None);
+    }
+}
@@ -0,0 +1,340 @@
+use std::time::Duration;
+
+use chrono::{Duration as ChronoDuration, FixedOffset, Months, NaiveDateTime, NaiveTime};
+use pest::Parser;
+use pest_derive::Parser;
+
+use crate::{parse_datetime, parse_time_of_day, Error, ParsedDateTime, Result, SECONDS_PER_DAY, SECONDS_PER_HOUR, SECONDS_PER_MINUTE, SECONDS_PER_WEEK};
+
+#[derive(Parser)]
+#[grammar = "grammars/intervals.pest"]
+#[grammar = "grammars/clock.pest"]
+#[grammar = "grammars/ranges.pest"]
+struct RangeParser;
+
+/// A span of time with a start and an end, or a bare length when nothing anchors it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interval {
+    /// A daily clock range such as `9am-5pm`.  An end at or before the start wraps past midnight, so `22:00-06:00` is
+    /// eight hours and `09:00-09:00` is a full day.
+    TimeOfDay { start: NaiveTime, end: NaiveTime },
+    /// An anchored range.  Both ends are wall-clock times in `offset` when the input had one.
+    DateTime { start: NaiveDateTime, end: NaiveDateTime, offset: Option<FixedOffset> },
+    /// An ISO 8601 interval given only as a duration, such as `PT36H`.  Years and months have no fixed length, so a
+    /// bare `P1M` is rejected.
+    Duration(Duration),
+}
+
+impl Interval {
+    /// The time from start to end, or `None` for a datetime range that ends before it starts.
+    pub fn length(&self) -> Option<Duration> {
+        match self {
+            Self::TimeOfDay { start, end } => {
+                let mut length = *end - *start;
+                if length <= ChronoDuration::zero() {
+                    length += ChronoDuration::days(1);
+                }
+                length.to_std().ok()
+            }
+            Self::DateTime { start, end, .. } => (*end - *start).to_std().ok(),
+            Self::Duration(duration) => Some(*duration),
+        }
+    }
+
+    /// Whether a time-of-day range crosses midnight.
+    pub fn wraps_midnight(&self) -> bool {
+        match self {
+            Self::TimeOfDay { start, end } => end <= start,
+            _ => false,
+        }
+    }
+}
+
+enum Endpoint {
+    Time(NaiveTime),
+    DateTime(ParsedDateTime),
+    /// Months, including years, are kept apart from the rest since their length depends on the date they meet.
+    Duration(Months, ChronoDuration),
+}
+
+/// Parses an interval with a start and an end.
+///
+/// Supported forms:
+/// - clock ranges: `9am-5pm`, `09:00 – 17:30`, `22:00-06:00`
+/// - ISO 8601: `2024-01-01T09:00/2024-01-01T17:00`, `2024-01-01/P1D`, `PT2H/2024-01-01T12:00`, `PT36H`
+/// - English: `from 9am to 5pm`, `between 2024-01-01 and 2024-01-08`, `9am until noon`
+///
+/// Ends may be times of day, anything [`crate::parse_datetime`] accepts, or durations.  Durations are ISO 8601 values
+/// (`P1Y2M`, `P1DT2H`) or anything [`crate::parse_duration`] accepts (`1h30m`).  Years and months are added by calendar
+/// and can only meet a datetime end.  An end given as a bare time after a datetime
+/// start (`2024-01-01T09:00/17:00`) falls on the start's date, or the next day if that would be before the start.
+#[inline(always)]
+pub fn parse_interval(input: impl AsRef<str>) -> Result<Interval> {
+    let input = input.as_ref();
+    let parse_result = RangeParser::parse(Rule::range, input.trim()).map_err(|_e| Error::invalid_time(input))?;
+
+    let Some(pair) = parse_result
+        .flat_map(|pair| pair.into_inner())
+        .next()
+    else {
+        unreachable!("Interval must have a form");
+    };
+
+    let endpoints = match pair.as_rule() {
+        Rule::range_end => {
+            let Endpoint::Duration(months, duration) = parse_endpoint(pair.as_str(), input)? else {
+                tracing::trace!("Interval without an end is not a duration: {}", input);
+                return Err(Error::invalid_time(input));
+            };
+            if months != Months::new(0) {
+                tracing::trace!("Interval without an end has no fixed length: {}", input);
+                return Err(Error::invalid_time(input));
+            }
+            return to_std(duration, input).map(Interval::Duration);
+        }
+        Rule::range_clock => pair
+            .into_inner()
+            .map(|inner_pair| parse_time_of_day(inner_pair.as_str()).map(Endpoint::Time))
+            .collect::<Result<Vec<_>>>()
+            .map_err(|_e| Error::invalid_time(input))?,
+        Rule::range_iso | Rule::range_between_and | Rule::range_from_to => pair
+            .into_inner()
+            .map(|inner_pair| parse_endpoint(inner_pair.as_str(), input))
+            .collect::<Result<Vec<_>>>()?,
+        _ => unreachable!("Unexpected rule in interval parsing: {:?}", pair.as_rule()),
+    };
+
+    let Ok([start, end]) = <[Endpoint; 2]>::try_from(endpoints) else {
+        unreachable!("Interval must have a start and an end");
+    };
+    resolve(start, end, input)
+}
+
+#[inline(always)]
+fn resolve(start: Endpoint, end: Endpoint, input: &str) -> Result<Interval> {
+    let interval = match (start, end) {
+        (Endpoint::Time(start), Endpoint::Time(end)) => Interval::TimeOfDay { start, end },
+        (Endpoint::Time(start), Endpoint::Duration(months, duration)) => {
+            if months != Months::new(0) || duration > ChronoDuration::seconds(SECONDS_PER_DAY as i64) {
+                tracing::trace!("Time of day range longer than a day: {}", input);
+                return Err(Error::invalid_time(input));
+            }
+            Interval::TimeOfDay {
+                start,
+                end: start.overflowing_add_signed(duration).0,
+            }
+        }
+        (Endpoint::DateTime(start), Endpoint::DateTime(end)) => {
+            let offset = start.offset.or(end.offset);
+            let end_datetime = match (start.offset, end.offset) {
+                (Some(start_offset), Some(end_offset)) => end.datetime - end_offset + start_offset,
+                _ => end.datetime,
+            };
+            anchored(start.datetime, end_datetime, offset, input)?
+        }
+        (Endpoint::DateTime(start), Endpoint::Time(end)) => {
+            let mut end_datetime = start.datetime.date().and_time(end);
+            if end_datetime < start.datetime {
+                end_datetime += ChronoDuration::days(1);
+            }
+            anchored(start.datetime, end_datetime, start.offset, input)?
+        }
+        (Endpoint::DateTime(start), Endpoint::Duration(months, duration)) => {
+            let end = start
+                .datetime
+                .checked_add_months(months)
+                .and_then(|datetime| datetime.checked_add_signed(duration))
+                .ok_or_else(|| Error::invalid_time(input))?;
+            anchored(start.datetime, end, start.offset, input)?
+        }
+        (Endpoint::Duration(months, duration), Endpoint::DateTime(end)) => {
+            let start = end
+                .datetime
+                .checked_sub_signed(duration)
+                .and_then(|datetime| datetime.checked_sub_months(months))
+                .ok_or_else(|| Error::invalid_time(input))?;
+            anchored(start, end.datetime, end.offset, input)?
+        }
+        _ => {
+            tracing::trace!("Interval ends cannot be combined: {}", input);
+            return Err(Error::invalid_time(input));
+        }
+    };
+
+    Ok(interval)
+}
+
+#[inline(always)]
+fn anchored(start: NaiveDateTime, end: NaiveDateTime, offset: Option<FixedOffset>, input: &str) -> Result<Interval> {
+    if end < start {
+        tracing::trace!("Interval ends before it starts: {}", input);
+        return Err(Error::invalid_time(input));
+    }
+    Ok(Interval::DateTime { start, end, offset })
+}
+
+#[inline(always)]
+fn parse_endpoint(text: &str, input: &str) -> Result<Endpoint> {
+    let text = text.trim();
+    if let Ok(time) = parse_time_of_day(text) {
+        return Ok(Endpoint::Time(time));
+    }
+    if let Ok(datetime) = parse_datetime(text) {
+        return Ok(Endpoint::DateTime(datetime));
+    }
+    if text.starts_with(['P', 'p']) {
+        let (months, duration) = parse_iso_duration(text, input)?;
+        return Ok(Endpoint::Duration(months, duration));
+    }
+    if RangeParser::parse(Rule::range_duration, text).is_ok() {
+        let duration = crate::parse_duration(text).map_err(|_e| Error::invalid_time(input))?;
+        return ChronoDuration::from_std(duration)
+            .map(|duration| Endpoint::Duration(Months::new(0), duration))
+            .map_err(|_e| Error::invalid_time(input));
+    }
+
+    tracing::trace!("Invalid interval end {:?} in: {}", text, input);
+    Err(Error::invalid_time(input))
+}
+
+/// Parses an ISO 8601 duration such as `P1Y2M10DT2H30M`.  Weeks and days are exact multiples of 24 hours.
+#[inline(always)]
+fn parse_iso_duration(text: &str, input: &str) -> Result<(Months, ChronoDuration)> {
+    let text = text.to_ascii_uppercase();
+    let parse_result = RangeParser::parse(Rule::iso_duration, &text).map_err(|_e| Error::invalid_time(input))?;
+
+    let (mut months, mut seconds) = (0u32, 0i64);
+    for pair in parse_result.flatten() {
+        let (months_per_unit, seconds_per_unit) = match pair.as_rule() {
+            Rule::iso_years => (12, 0),
+            Rule::iso_months => (1, 0),
+            Rule::iso_weeks => (0, SECONDS_PER_WEEK),
+            Rule::iso_days => (0, SECONDS_PER_DAY),
+            Rule::iso_hours => (0, SECONDS_PER_HOUR),
+            Rule::iso_minutes => (0, SECONDS_PER_MINUTE),
+            Rule::iso_seconds => (0, 1),
+            _ => continue,
+        };
+        let digits = pair.into_inner().as_str();
+        let quantity = digits.parse::<u32>().ok();
+        let added = quantity
+            .and_then(|quantity| quantity.checked_mul(months_per_unit))
+            .and_then(|added| months.checked_add(added))
+            .zip(
+                quantity
+                    .and_then(|quantity| i64::from(quantity).checked_mul(seconds_per_unit as i64))
+                    .and_then(|added| seconds.checked_add(added)),
+            );
+        let Some((total_months, total_seconds)) = added else {
+            tracing::trace!("Duration component out of range: {}", digits);
+            return Err(Error::invalid_time(input));
+        };
+        (months, seconds) = (total_months, total_seconds);
+    }
+
+    let duration = ChronoDuration::try_seconds(seconds).ok_or_else(|| Error::invalid_time(input))?;
+    Ok((Months::new(months), duration))
+}
+
+#[inline(always)]
+fn to_std(duration: ChronoDuration, input: &str) -> Result<Duration> {
+    duration
+        .to_std()
+        .map_err(|_e| Error::invalid_time(input))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{SECONDS_PER_HOUR, SECONDS_PER_MINUTE};
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).expect("time must be valid for this test")
+    }
+
+    fn datetime(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .and_then(|date| date.and_hms_opt(hour, minute, 0))
+            .expect("datetime must be valid for this test")
+    }
+
+    fn hours(hours: u64) -> Duration {
+        Duration::from_secs(hours * SECONDS_PER_HOUR)
+    }
+
+    #[rstest]
+    #[case::clock_12h("9am-5pm", Interval::TimeOfDay { start: time(9, 0), end: time(17, 0) }, hours(8))]
+    #[case::clock_24h_spaced("09:00 – 17:30", Interval::TimeOfDay { start: time(9, 0), end: time(17, 30) }, hours(8) + Duration::from_secs(30 * SECONDS_PER_MINUTE))]
+    #[case::clock_wraps("22:00-06:00", Interval::TimeOfDay { start: time(22, 0), end: time(6, 0) }, hours(8))]
+    #[case::clock_full_day("09:00-09:00", Interval::TimeOfDay { start: time(9, 0), end: time(9, 0) }, hours(24))]
+    #[case::from_to("from 9am to 5pm", Interval::TimeOfDay { start: time(9, 0), end: time(17, 0) }, hours(8))]
+    #[case::until_noon("9am until noon", Interval::TimeOfDay { start: time(9, 0), end: time(12, 0) }, hours(3))]
+    #[case::between_times("between 11pm and 1am", Interval::TimeOfDay { start: time(23, 0), end: time(1, 0) }, hours(2))]
+    #[case::time_and_duration("22:00/PT4H", Interval::TimeOfDay { start: time(22, 0), end: time(2, 0) }, hours(4))]
+    #[case::iso_start_end("2024-01-01T09:00/2024-01-01T17:00", Interval::DateTime { start: datetime(2024, 1, 1, 9, 0), end: datetime(2024, 1, 1, 17, 0), offset: None }, hours(8))]
+    #[case::iso_start_duration("2024-01-01/P1D", Interval::DateTime { start: datetime(2024, 1, 1, 0, 0), end: datetime(2024, 1, 2, 0, 0), offset: None }, hours(24))]
+    #[case::iso_duration_end("PT2H/2024-01-01T12:00", Interval::DateTime { start: datetime(2024, 1, 1, 10, 0), end: datetime(2024, 1, 1, 12, 0), offset: None }, hours(2))]
+    #[case::iso_duration("PT36H", Interval::Duration(hours(36)), hours(36))]
+    #[case::iso_weeks("P1W", Interval::Duration(hours(168)), hours(168))]
+    #[case::iso_abbreviated_end("2024-01-01T09:00/17:00", Interval::DateTime { start: datetime(2024, 1, 1, 9, 0), end: datetime(2024, 1, 1, 17, 0), offset: None }, hours(8))]
+    #[case::iso_abbreviated_end_next_day("2024-01-01T22:00/02:00", Interval::DateTime { start: datetime(2024, 1, 1, 22, 0), end: datetime(2024, 1, 2, 2, 0), offset: None }, hours(4))]
+    #[case::shorthand_duration("2024-01-01T09:00/1h30m", Interval::DateTime { start: datetime(2024, 1, 1, 9, 0), end: datetime(2024, 1, 1, 10, 30), offset: None }, Duration::from_secs(90 * SECONDS_PER_MINUTE))]
+    #[case::mixed_offsets("2024-01-01T09:00:00+01:00/2024-01-01T17:00:00Z", Interval::DateTime { start: datetime(2024, 1, 1, 9, 0), end: datetime(2024, 1, 1, 18, 0), offset: FixedOffset::east_opt(3600) }, hours(9))]
+    #[case::between_dates("between 2024-01-01 and 2024-01-08", Interval::DateTime { start: datetime(2024, 1, 1, 0, 0), end: datetime(2024, 1, 8, 0, 0), offset: None }, hours(168))]
+    #[case::iso_months("2024-01-31/P1M", Interval::DateTime { start: datetime(2024, 1, 31, 0, 0), end: datetime(2024, 2, 29, 0, 0), offset: None }, hours(29 * 24))]
+    #[case::iso_years_before_end("P1Y/2024-01-01", Interval::DateTime { start: datetime(2023, 1, 1, 0, 0), end: datetime(2024, 1, 1, 0, 0), offset: None }, hours(365 * 24))]
+    #[case::iso_full("2024-01-01T00:00/P1Y2M3DT4H5M6S", Interval::DateTime { start: datetime(2024, 1, 1, 0, 0), end: datetime(2025, 3, 4, 4, 5) + ChronoDuration::seconds(6), offset: None }, Duration::from_secs((425 + 3) * 86400 + 4 * SECONDS_PER_HOUR + 5 * SECONDS_PER_MINUTE + 6))]
+    #[case::iso_lowercase("2024-01-01/p1m", Interval::DateTime { start: datetime(2024, 1, 1, 0, 0), end: datetime(2024, 2, 1, 0, 0), offset: None }, hours(31 * 24))]
+    #[case::from_datetimes("from 2024-01-01 09:00 to 2024-01-02 09:00", Interval::DateTime { start: datetime(2024, 1, 1, 9, 0), end: datetime(2024, 1, 2, 9, 0), offset: None }, hours(24))]
+    fn test_parse_interval(#[case] input: &str, #[case] expected: Interval, #[case] length: Duration) {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .try_init()
+            .ok();
+
+        match parse_interval(input) {
+            Ok(actual) => {
+                assert_eq!(actual, expected);
+                assert_eq!(actual.length(), Some(length));
+            }
+            Err(e) => panic!("Expected Ok, got Err: {:?}", e),
+        }
+    }
+
+    #[rstest]
+    #[case::empty("")]
+    #[case::single_time("9am")]
+    #[case::single_date("2024-01-01")]
+    #[case::ends_before_start("2024-01-02/2024-01-01")]
+    #[case::two_durations("PT1H/PT2H")]
+    #[case::duration_then_time("PT1H/17:00")]
+    #[case::negative_duration("2024-01-01/-PT1H")]
+    #[case::invalid_end("9am-25:00")]
+    #[case::trailing_text("2024-01-01T09:00/1h30m please")]
+    #[case::missing_end("from 9am to")]
+    #[case::time_range_longer_than_day("09:00/P2D")]
+    #[case::bare_months("P1M")]
+    #[case::time_and_months("09:00/P1M")]
+    #[case::empty_duration("2024-01-01/P")]
+    #[case::empty_time("2024-01-01/P1DT")]
+    #[case::out_of_order_components("2024-01-01/P1D1Y")]
+    fn test_parse_interval_invalid(#[case] input: &str) {
+        match parse_interval(input) {
+            Ok(actual) => panic!("Expected Err, got Ok {:?}", actual),
+            Err(e) => assert_eq!(e.to_string(), Error::invalid_time(input).to_string()),
+        }
+    }
+
+    #[test]
+    fn test_length_reversed() {
+        let reversed = Interval::DateTime {
+            start: datetime(2024, 1, 2, 0, 0),
+            end: datetime(2024, 1, 1, 0, 0),
+            offset: None,
+        };
+        assert_eq!(reversed.length(), None);
+    }
+}
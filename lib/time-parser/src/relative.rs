@@ -0,0 +1,261 @@
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, FixedOffset, NaiveDate, NaiveTime, TimeZone, Weekday};
+use pest::iterators::Pair;
+use pest::Parser;
+use pest_derive::Parser;
+
+use crate::time_of_day::parse_time_of_day;
+use crate::{Clock, Error, Result, Unit};
+
+#[derive(Parser)]
+#[grammar = "grammars/intervals.pest"]
+#[grammar = "grammars/clock.pest"]
+#[grammar = "grammars/relative.pest"]
+struct RelativeParser;
+
+/// Resolves a relative expression against the clock's current time.
+///
+/// Supported expressions:
+/// - `now`, `now + 2d`, `tomorrow - 1h`
+/// - `in 3 hours`, `in an hour`, `5 minutes ago`
+/// - `today`, `tomorrow`, `yesterday`, optionally followed by a time: `tomorrow at 9am`, `yesterday noon`
+/// - weekdays: `friday`, `this friday`, `next friday`, `last friday`, optionally followed by a time
+///
+/// Quantities are anything [`crate::parse_duration`] accepts.  Day words without a time resolve to midnight.
+///
+/// Weekdays are resolved relative to today's date:
+/// - `friday` and `this friday` are the next Friday on or after today, so they mean today when said on a Friday.
+/// - `next friday` is the first Friday strictly after today, so `next monday` said on a Monday is seven days later
+///   and said on a Sunday is the following day.
+/// - `last friday` is the most recent Friday strictly before today.
+///
+/// Calendar arithmetic happens in the clock's UTC offset.
+#[inline(always)]
+pub fn parse_relative_datetime(expression: impl AsRef<str>, clock: impl Clock) -> Result<DateTime<FixedOffset>> {
+    let expression = expression.as_ref();
+    let parse_result = RelativeParser::parse(Rule::relative_datetime, expression).map_err(|_e| Error::invalid_time(expression))?;
+    let now = clock.now();
+
+    let mut resolved = None;
+    for pair in parse_result.flat_map(|pair| pair.into_inner()) {
+        match pair.as_rule() {
+            Rule::rel_in => {
+                let quantity = parse_quantity(pair, expression)?;
+                resolved = Some(checked_add(now, quantity, expression)?);
+            }
+            Rule::rel_ago => {
+                let quantity = parse_quantity(pair, expression)?;
+                resolved = Some(checked_add(now, -quantity, expression)?);
+            }
+            Rule::rel_anchor => resolved = Some(parse_anchor(pair, now, expression)?),
+            Rule::rel_adjustment => {
+                let Some(anchor) = resolved else {
+                    unreachable!("Adjustments must follow an anchor");
+                };
+                let mut inner_pairs = pair.into_inner();
+                let negative = inner_pairs
+                    .next()
+                    .is_some_and(|sign| sign.as_rule() == Rule::rel_minus);
+                let quantity = match inner_pairs.next() {
+                    Some(quantity) => parse_quantity_inner(quantity, expression)?,
+                    None => unreachable!("Adjustment must have a quantity"),
+                };
+                resolved = Some(checked_add(anchor, if negative { -quantity } else { quantity }, expression)?);
+            }
+            Rule::EOI => {}
+            _ => unreachable!("Unexpected rule in relative datetime parsing: {:?}", pair.as_rule()),
+        }
+    }
+
+    resolved.ok_or_else(|| {
+        tracing::trace!("No relative datetime found in expression: {}", expression);
+        Error::invalid_time(expression)
+    })
+}
+
+#[inline(always)]
+fn parse_anchor(pair: Pair<Rule>, now: DateTime<FixedOffset>, expression: &str) -> Result<DateTime<FixedOffset>> {
+    let today = now.date_naive();
+    let mut date = today;
+    let mut time = NaiveTime::MIN;
+
+    for inner_pair in pair.into_inner() {
+        match inner_pair.as_rule() {
+            Rule::rel_now => return Ok(now),
+            Rule::rel_today => date = today,
+            Rule::rel_tomorrow => {
+                date = today
+                    .succ_opt()
+                    .ok_or_else(|| Error::invalid_time(expression))?
+            }
+            Rule::rel_yesterday => {
+                date = today
+                    .pred_opt()
+                    .ok_or_else(|| Error::invalid_time(expression))?
+            }
+            Rule::rel_weekday => date = resolve_weekday(inner_pair, today).ok_or_else(|| Error::invalid_time(expression))?,
+            Rule::clock_time => time = parse_time_of_day(inner_pair.as_str()).map_err(|_e| Error::invalid_time(expression))?,
+            _ => unreachable!("Unexpected rule in relative anchor parsing: {:?}", inner_pair.as_rule()),
+        }
+    }
+
+    now.offset()
+        .from_local_datetime(&date.and_time(time))
+        .single()
+        .ok_or_else(|| Error::invalid_time(expression))
+}
+
+#[inline(always)]
+fn resolve_weekday(pair: Pair<Rule>, today: NaiveDate) -> Option<NaiveDate> {
+    let mut modifier = Rule::rel_this;
+    let mut weekday = Weekday::Mon;
+
+    for inner_pair in pair.into_inner() {
+        match inner_pair.as_rule() {
+            Rule::rel_next | Rule::rel_last | Rule::rel_this => modifier = inner_pair.as_rule(),
+            Rule::rel_monday => weekday = Weekday::Mon,
+            Rule::rel_tuesday => weekday = Weekday::Tue,
+            Rule::rel_wednesday => weekday = Weekday::Wed,
+            Rule::rel_thursday => weekday = Weekday::Thu,
+            Rule::rel_friday => weekday = Weekday::Fri,
+            Rule::rel_saturday => weekday = Weekday::Sat,
+            Rule::rel_sunday => weekday = Weekday::Sun,
+            _ => unreachable!("Unexpected rule in weekday parsing: {:?}", inner_pair.as_rule()),
+        }
+    }
+
+    let days_ahead = (7 + weekday.num_days_from_monday() - today.weekday().num_days_from_monday()) % 7;
+    let days = match modifier {
+        Rule::rel_next if days_ahead == 0 => 7,
+        Rule::rel_last => -((7 - days_ahead as i64) % 7) - if days_ahead == 0 { 7 } else { 0 },
+        _ => days_ahead as i64,
+    };
+    today.checked_add_signed(ChronoDuration::days(days))
+}
+
+#[inline(always)]
+fn parse_quantity(pair: Pair<Rule>, expression: &str) -> Result<ChronoDuration> {
+    match pair
+        .into_inner()
+        .find(|inner_pair| inner_pair.as_rule() == Rule::rel_quantity)
+    {
+        Some(quantity) => parse_quantity_inner(quantity, expression),
+        None => unreachable!("Relative expression must have a quantity"),
+    }
+}
+
+#[inline(always)]
+fn parse_quantity_inner(pair: Pair<Rule>, expression: &str) -> Result<ChronoDuration> {
+    let Some(inner_pair) = pair.into_inner().next() else {
+        unreachable!("Quantity must have a form");
+    };
+
+    let duration = match inner_pair.as_rule() {
+        Rule::duration => crate::durations::parse_duration(inner_pair.as_str()),
+        Rule::rel_single_unit => inner_pair
+            .into_inner()
+            .as_str()
+            .parse::<Unit>()
+            .map(|unit| unit.duration()),
+        _ => unreachable!("Unexpected rule in quantity parsing: {:?}", inner_pair.as_rule()),
+    };
+
+    duration
+        .ok()
+        .and_then(|duration| ChronoDuration::from_std(duration).ok())
+        .ok_or_else(|| Error::invalid_time(expression))
+}
+
+#[inline(always)]
+fn checked_add(datetime: DateTime<FixedOffset>, duration: ChronoDuration, expression: &str) -> Result<DateTime<FixedOffset>> {
+    datetime
+        .checked_add_signed(duration)
+        .ok_or_else(|| {
+            tracing::trace!("Relative datetime out of range: {}", expression);
+            Error::invalid_time(expression)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::FixedClock;
+
+    fn clock(value: &str) -> FixedClock {
+        FixedClock(DateTime::parse_from_rfc3339(value).expect("clock must be valid for this test"))
+    }
+
+    // 2024-10-14 is a Monday
+    const NOW: &str = "2024-10-14T10:30:00+02:00";
+
+    #[rstest]
+    #[case::now("now", "2024-10-14T10:30:00+02:00")]
+    #[case::in_3_hours("in 3 hours", "2024-10-14T13:30:00+02:00")]
+    #[case::in_an_hour("in an hour", "2024-10-14T11:30:00+02:00")]
+    #[case::in_shorthand("in 1h30m", "2024-10-14T12:00:00+02:00")]
+    #[case::in_hms("in 01:00:00", "2024-10-14T11:30:00+02:00")]
+    #[case::minutes_ago("5 minutes ago", "2024-10-14T10:25:00+02:00")]
+    #[case::a_day_ago("a day ago", "2024-10-13T10:30:00+02:00")]
+    #[case::now_plus("now + 2d", "2024-10-16T10:30:00+02:00")]
+    #[case::now_minus_compact("now-90m", "2024-10-14T09:00:00+02:00")]
+    #[case::now_chained("now + 1d - 1h", "2024-10-15T09:30:00+02:00")]
+    #[case::today("today", "2024-10-14T00:00:00+02:00")]
+    #[case::tomorrow_at_9am("tomorrow at 9am", "2024-10-15T09:00:00+02:00")]
+    #[case::yesterday_noon("yesterday noon", "2024-10-13T12:00:00+02:00")]
+    #[case::tomorrow_24h("Tomorrow 17:45", "2024-10-15T17:45:00+02:00")]
+    #[case::tomorrow_minus("tomorrow - 1h", "2024-10-14T23:00:00+02:00")]
+    #[case::tomorrow_phrase("tomorrow at quarter past 3pm", "2024-10-15T15:15:00+02:00")]
+    #[case::next_friday("next friday", "2024-10-18T00:00:00+02:00")]
+    #[case::next_friday_at("next fri at 5pm", "2024-10-18T17:00:00+02:00")]
+    #[case::friday("friday", "2024-10-18T00:00:00+02:00")]
+    #[case::last_friday("last friday", "2024-10-11T00:00:00+02:00")]
+    #[case::next_monday_on_monday("next monday", "2024-10-21T00:00:00+02:00")]
+    #[case::this_monday_on_monday("this monday", "2024-10-14T00:00:00+02:00")]
+    #[case::monday_on_monday("monday at noon", "2024-10-14T12:00:00+02:00")]
+    #[case::last_monday_on_monday("last monday", "2024-10-07T00:00:00+02:00")]
+    #[case::next_sunday("next sunday", "2024-10-20T00:00:00+02:00")]
+    #[case::last_tuesday("last tuesday", "2024-10-08T00:00:00+02:00")]
+    fn test_parse_relative_datetime(#[case] expression: &str, #[case] expected: &str) {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .try_init()
+            .ok();
+
+        let expected = DateTime::parse_from_rfc3339(expected).expect("expected must be valid for this test");
+        let result = parse_relative_datetime(expression, clock(NOW));
+        match result {
+            Ok(result) => {
+                assert_eq!(result, expected);
+                assert_eq!(result.offset(), expected.offset());
+            }
+            Err(e) => panic!("Expected Ok, got Err: {:?}", e),
+        }
+    }
+
+    #[rstest]
+    #[case::next_monday_on_sunday("2024-10-13T08:00:00Z", "next monday", "2024-10-14T00:00:00Z")]
+    #[case::next_monday_on_tuesday("2024-10-15T08:00:00Z", "next monday", "2024-10-21T00:00:00Z")]
+    #[case::last_sunday_on_monday("2024-10-14T08:00:00Z", "last sunday", "2024-10-13T00:00:00Z")]
+    fn test_parse_relative_weekday(#[case] now: &str, #[case] expression: &str, #[case] expected: &str) {
+        let expected = DateTime::parse_from_rfc3339(expected).expect("expected must be valid for this test");
+        let result = parse_relative_datetime(expression, clock(now)).expect("expression must be valid for this test");
+        assert_eq!(result, expected);
+    }
+
+    #[rstest]
+    #[case::empty("")]
+    #[case::missing_quantity("in hours")]
+    #[case::ago_without_quantity("ago")]
+    #[case::unknown_word("someday")]
+    #[case::invalid_time("tomorrow at 25:00")]
+    #[case::adjustment_without_anchor("+ 2d")]
+    #[case::trailing_text("next friday maybe")]
+    fn test_parse_relative_datetime_invalid(#[case] expression: &str) {
+        let result = parse_relative_datetime(expression, clock(NOW));
+        match result {
+            Ok(actual) => panic!("Expected Err, got Ok {:?}", actual),
+            Err(e) => assert_eq!(e.to_string(), Error::invalid_time(expression).to_string()),
+        }
+    }
+}
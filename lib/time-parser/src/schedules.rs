@@ -0,0 +1,347 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono::{Datelike, Duration as ChronoDuration, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use pest::iterators::Pair;
+use pest::Parser;
+use pest_derive::Parser;
+
+use crate::time_of_day::parse_time_of_day;
+use crate::{Error, Result, SECONDS_PER_DAY};
+
+#[derive(Parser)]
+#[grammar = "grammars/intervals.pest"]
+#[grammar = "grammars/clock.pest"]
+#[grammar = "grammars/schedules.pest"]
+struct ScheduleParser;
+
+/// A recurring schedule parsed from an English phrase such as `every 10m between 9am and 5pm`.
+///
+/// Intervals shorter than a day restart every day, at midnight or at the start of the window, so `every 7h` fires
+/// at 00:00, 07:00, 14:00 and 21:00.  Window ends are inclusive and a window whose end is before its start runs past
+/// midnight.  Intervals of a day or more are anchored at 1970-01-01 00:00 and cannot be limited to weekdays.
+/// An empty `days` list means every day of the week.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Schedule {
+    Interval {
+        interval: Duration,
+        days: Vec<Weekday>,
+        window: Option<(NaiveTime, NaiveTime)>,
+    },
+    TimesOfDay {
+        days: Vec<Weekday>,
+        times: Vec<NaiveTime>,
+    },
+}
+
+impl Schedule {
+    /// Fire times strictly after `after`, in ascending order.
+    pub fn fire_times(&self, after: NaiveDateTime) -> FireTimes {
+        FireTimes::new(self.clone(), after)
+    }
+
+    fn days(&self) -> &[Weekday] {
+        match self {
+            Self::Interval { days, .. } | Self::TimesOfDay { days, .. } => days,
+        }
+    }
+
+    fn fires_on(&self, date: NaiveDate) -> bool {
+        self.days().is_empty() || self.days().contains(&date.weekday())
+    }
+
+    /// The first fire time on `date` strictly after `after`.  Interval fire times are computed from the start of the
+    /// day or window, so short intervals do not enumerate the whole day.
+    fn next_fire_time_on(&self, date: NaiveDate, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        if !self.fires_on(date) {
+            return None;
+        }
+        match self {
+            Self::TimesOfDay { times, .. } => times
+                .iter()
+                .map(|time| date.and_time(*time))
+                .find(|fire_time| *fire_time > after),
+            Self::Interval { interval, window, .. } => {
+                let start = date.and_time(window.map_or(NaiveTime::MIN, |(start, _)| start));
+                let end = match window {
+                    Some((window_start, window_end)) if window_end < window_start => date
+                        .succ_opt()
+                        .map(|next| next.and_time(*window_end)),
+                    Some((_, window_end)) => Some(date.and_time(*window_end)),
+                    None => date
+                        .succ_opt()
+                        .map(|next| next.and_time(NaiveTime::MIN) - ChronoDuration::nanoseconds(1)),
+                }?;
+                let fire_time = if after < start {
+                    start
+                } else {
+                    let interval_nanos = i64::try_from(interval.as_nanos()).ok()?;
+                    let steps = (after - start).num_nanoseconds()? / interval_nanos + 1;
+                    start.checked_add_signed(ChronoDuration::nanoseconds(steps.checked_mul(interval_nanos)?))?
+                };
+                (fire_time <= end).then_some(fire_time)
+            }
+        }
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = Error;
+
+    fn from_str(phrase: &str) -> Result<Self> {
+        parse_schedule(phrase)
+    }
+}
+
+/// Parses phrases like `every 2h`, `every day at 17:30`, `every monday and thursday at noon`,
+/// `every weekday at 9am` or `every 10m between 9am and 5pm`.
+///
+/// The interval uses the same shorthand grammar as [`crate::parse_duration_shorthand`], so `every 1h30m` works.
+#[inline(always)]
+pub fn parse_schedule(phrase: impl AsRef<str>) -> Result<Schedule> {
+    let phrase = phrase.as_ref();
+    let parse_result = ScheduleParser::parse(Rule::schedule, phrase).map_err(|_e| Error::invalid_recurrence(phrase))?;
+
+    for pair in parse_result.flat_map(|pair| pair.into_inner()) {
+        match pair.as_rule() {
+            Rule::schedule_interval => return parse_interval_inner(pair, phrase),
+            Rule::schedule_times => return parse_times_inner(pair, phrase),
+            _ => tracing::trace!("Unexpected top-level rule: {:?}", pair.as_rule()),
+        }
+    }
+
+    tracing::trace!("No schedule found in phrase: {}", phrase);
+    Err(Error::invalid_recurrence(phrase))
+}
+
+#[inline(always)]
+fn parse_interval_inner(pair: Pair<Rule>, phrase: &str) -> Result<Schedule> {
+    let mut interval = Duration::ZERO;
+    let mut days = Vec::new();
+    let mut window = None;
+
+    for inner_pair in pair.into_inner() {
+        match inner_pair.as_rule() {
+            Rule::duration_shorthand => interval = crate::durations::parse_duration_shorthand(inner_pair.as_str()).map_err(|_e| Error::invalid_recurrence(phrase))?,
+            Rule::schedule_on => days = parse_days(inner_pair),
+            Rule::schedule_window => {
+                let mut times = inner_pair
+                    .into_inner()
+                    .map(|time| parse_time_of_day(time.as_str()).map_err(|_e| Error::invalid_time(phrase)));
+                match (times.next(), times.next()) {
+                    (Some(start), Some(end)) => window = Some((start?, end?)),
+                    _ => unreachable!("Window must have a start and an end"),
+                }
+            }
+            _ => unreachable!("Unexpected rule in interval schedule parsing: {:?}", inner_pair.as_rule()),
+        }
+    }
+
+    if interval.is_zero() {
+        tracing::trace!("Schedule interval must be positive: {}", phrase);
+        return Err(Error::invalid_recurrence(phrase));
+    }
+    if interval.as_secs() >= SECONDS_PER_DAY && (!days.is_empty() || window.is_some()) {
+        tracing::trace!("Intervals of a day or more cannot be limited to days or windows: {}", phrase);
+        return Err(Error::invalid_recurrence(phrase));
+    }
+
+    Ok(Schedule::Interval { interval, days, window })
+}
+
+#[inline(always)]
+fn parse_times_inner(pair: Pair<Rule>, phrase: &str) -> Result<Schedule> {
+    let mut days = Vec::new();
+    let mut times = Vec::new();
+
+    for inner_pair in pair.into_inner() {
+        match inner_pair.as_rule() {
+            Rule::schedule_day => {}
+            Rule::schedule_at => {
+                for time in inner_pair.into_inner() {
+                    times.push(parse_time_of_day(time.as_str()).map_err(|_e| Error::invalid_time(phrase))?);
+                }
+            }
+            _ => days.extend(parse_day(inner_pair)),
+        }
+    }
+
+    if times.is_empty() {
+        times.push(NaiveTime::MIN);
+    }
+    times.sort_unstable();
+    times.dedup();
+    days.sort_unstable_by_key(|day: &Weekday| day.num_days_from_monday());
+    days.dedup();
+
+    Ok(Schedule::TimesOfDay { days, times })
+}
+
+#[inline(always)]
+fn parse_days(pair: Pair<Rule>) -> Vec<Weekday> {
+    let mut days: Vec<Weekday> = pair.into_inner().flat_map(parse_day).collect();
+    days.sort_unstable_by_key(|day| day.num_days_from_monday());
+    days.dedup();
+    days
+}
+
+#[inline(always)]
+fn parse_day(pair: Pair<Rule>) -> Vec<Weekday> {
+    match pair.as_rule() {
+        Rule::schedule_monday => vec![Weekday::Mon],
+        Rule::schedule_tuesday => vec![Weekday::Tue],
+        Rule::schedule_wednesday => vec![Weekday::Wed],
+        Rule::schedule_thursday => vec![Weekday::Thu],
+        Rule::schedule_friday => vec![Weekday::Fri],
+        Rule::schedule_saturday => vec![Weekday::Sat],
+        Rule::schedule_sunday => vec![Weekday::Sun],
+        Rule::schedule_weekday => vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri],
+        Rule::schedule_weekend => vec![Weekday::Sat, Weekday::Sun],
+        _ => unreachable!("Unexpected rule in day parsing: {:?}", pair.as_rule()),
+    }
+}
+
+/// Iterator over the fire times of a [`Schedule`].
+#[derive(Clone, Debug)]
+pub struct FireTimes {
+    schedule: Schedule,
+    after: NaiveDateTime,
+    day: Option<NaiveDate>,
+}
+
+impl FireTimes {
+    fn new(schedule: Schedule, after: NaiveDateTime) -> Self {
+        // Start a day early so windows running past midnight are not missed
+        let day = after.date().pred_opt().or(Some(after.date()));
+        Self { schedule, after, day }
+    }
+
+    fn next_anchored(&mut self, interval: Duration) -> Option<NaiveDateTime> {
+        let epoch = NaiveDate::from_ymd_opt(1970, 1, 1)?.and_time(NaiveTime::MIN);
+        let elapsed = self.after - epoch;
+        let elapsed_nanos = elapsed.num_seconds() as i128 * 1_000_000_000 + elapsed.subsec_nanos() as i128;
+        let interval_nanos = interval.as_nanos() as i128;
+        let steps = elapsed_nanos.div_euclid(interval_nanos) + 1;
+        let offset = steps.checked_mul(interval_nanos)?;
+        let next = epoch.checked_add_signed(ChronoDuration::seconds(i64::try_from(offset.div_euclid(1_000_000_000)).ok()?))?;
+        let next = next.checked_add_signed(ChronoDuration::nanoseconds(offset.rem_euclid(1_000_000_000) as i64))?;
+        self.after = next;
+        Some(next)
+    }
+}
+
+impl Iterator for FireTimes {
+    type Item = NaiveDateTime;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Schedule::Interval { interval, .. } = self.schedule {
+            if interval.as_secs() >= SECONDS_PER_DAY {
+                return self.next_anchored(interval);
+            }
+        }
+
+        loop {
+            let day = self.day?;
+            if let Some(fire_time) = self.schedule.next_fire_time_on(day, self.after) {
+                self.after = fire_time;
+                return Some(fire_time);
+            }
+            self.day = day.succ_opt();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{SECONDS_PER_HOUR, SECONDS_PER_MINUTE};
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).expect("time must be valid for this test")
+    }
+
+    fn datetime(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").expect("datetime must be valid for this test")
+    }
+
+    #[rstest]
+    #[case::every_2h("every 2h", Schedule::Interval { interval: Duration::from_secs(2 * SECONDS_PER_HOUR), days: vec![], window: None })]
+    #[case::every_1h30m("every 1h30m", Schedule::Interval { interval: Duration::from_secs(SECONDS_PER_HOUR + 30 * SECONDS_PER_MINUTE), days: vec![], window: None })]
+    #[case::every_15_minutes("Every 15 minutes", Schedule::Interval { interval: Duration::from_secs(15 * SECONDS_PER_MINUTE), days: vec![], window: None })]
+    #[case::every_10m_between("every 10m between 9am and 5pm", Schedule::Interval { interval: Duration::from_secs(10 * SECONDS_PER_MINUTE), days: vec![], window: Some((time(9, 0), time(17, 0))) })]
+    #[case::every_5m_on_weekdays("every 5m on weekdays from 08:30 to 9:15 pm", Schedule::Interval { interval: Duration::from_secs(5 * SECONDS_PER_MINUTE), days: vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri], window: Some((time(8, 30), time(21, 15))) })]
+    #[case::every_day_at("every day at 17:30", Schedule::TimesOfDay { days: vec![], times: vec![time(17, 30)] })]
+    #[case::every_day("every day", Schedule::TimesOfDay { days: vec![], times: vec![time(0, 0)] })]
+    #[case::every_monday_and_thursday("every monday and thursday at noon", Schedule::TimesOfDay { days: vec![Weekday::Mon, Weekday::Thu], times: vec![time(12, 0)] })]
+    #[case::every_weekday("every weekday at 9am", Schedule::TimesOfDay { days: vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri], times: vec![time(9, 0)] })]
+    #[case::every_weekend("every weekend at 10:15 a.m.", Schedule::TimesOfDay { days: vec![Weekday::Sat, Weekday::Sun], times: vec![time(10, 15)] })]
+    #[case::several_days_and_times("every fri, mon and wed at 9am and 12:30 pm", Schedule::TimesOfDay { days: vec![Weekday::Mon, Weekday::Wed, Weekday::Fri], times: vec![time(9, 0), time(12, 30)] })]
+    #[case::midnight("every sunday at midnight", Schedule::TimesOfDay { days: vec![Weekday::Sun], times: vec![time(0, 0)] })]
+    #[case::twelve_am("every day at 12am and 12pm", Schedule::TimesOfDay { days: vec![], times: vec![time(0, 0), time(12, 0)] })]
+    fn test_parse_schedule(#[case] phrase: &str, #[case] expected: Schedule) {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .try_init()
+            .ok();
+
+        let result = parse_schedule(phrase);
+        match result {
+            Ok(result) => assert_eq!(result, expected),
+            Err(e) => panic!("Expected Ok, got Err: {:?}", e),
+        }
+    }
+
+    #[rstest]
+    #[case::missing_every("2h")]
+    #[case::zero_interval("every 0s")]
+    #[case::no_unit("every 15")]
+    #[case::invalid_12h_hour("every day at 13pm")]
+    #[case::invalid_24h_time("every day at 24:00")]
+    #[case::long_interval_with_days("every 2d on mondays")]
+    #[case::trailing_text("every day at noon please")]
+    fn test_parse_schedule_invalid(#[case] phrase: &str) {
+        let result = parse_schedule(phrase);
+        assert!(result.is_err(), "expected Err, got Ok {:?}", result);
+    }
+
+    #[rstest]
+    #[case::every_7h("every 7h", "2024-01-01 15:00", &["2024-01-01 21:00", "2024-01-02 00:00", "2024-01-02 07:00"])]
+    #[case::every_10m_between("every 10m between 9am and 5pm", "2024-01-01 16:45", &["2024-01-01 16:50", "2024-01-01 17:00", "2024-01-02 09:00"])]
+    #[case::window_past_midnight("every 1h between 11pm and 1am", "2024-01-01 00:30", &["2024-01-01 01:00", "2024-01-01 23:00", "2024-01-02 00:00", "2024-01-02 01:00"])]
+    #[case::strictly_after("every day at 17:30", "2024-01-01 17:30", &["2024-01-02 17:30", "2024-01-03 17:30"])]
+    #[case::monday_and_thursday("every monday and thursday at noon", "2024-01-01 13:00", &["2024-01-04 12:00", "2024-01-08 12:00", "2024-01-11 12:00"])]
+    #[case::weekdays("every weekday at 9am", "2024-01-05 10:00", &["2024-01-08 09:00", "2024-01-09 09:00"])]
+    #[case::every_2d_anchored("every 2d", "2024-01-01 12:00", &["2024-01-02 00:00", "2024-01-04 00:00"])]
+    fn test_fire_times(#[case] phrase: &str, #[case] after: &str, #[case] expected: &[&str]) {
+        let schedule = parse_schedule(phrase).expect("schedule must be valid for this test");
+        let result: Vec<NaiveDateTime> = schedule
+            .fire_times(datetime(after))
+            .take(expected.len())
+            .collect();
+        let expected: Vec<NaiveDateTime> = expected
+            .iter()
+            .map(|value| datetime(value))
+            .collect();
+        assert_eq!(result, expected);
+    }
+
+    #[rstest]
+    #[case::every_1ms("every 1ms", "2024-01-01 15:00:00.000000000", &["2024-01-01 15:00:00.001000000", "2024-01-01 15:00:00.002000000"])]
+    #[case::every_1us("every 1us", "2024-01-01 23:59:59.999998000", &["2024-01-01 23:59:59.999999000", "2024-01-02 00:00:00.000000000", "2024-01-02 00:00:00.000001000"])]
+    #[case::every_1ns_in_window("every 1ns between 9am and 5pm", "2024-01-01 17:00:00.000000000", &["2024-01-02 09:00:00.000000000", "2024-01-02 09:00:00.000000001"])]
+    fn test_fire_times_subsecond(#[case] phrase: &str, #[case] after: &str, #[case] expected: &[&str]) {
+        let schedule = parse_schedule(phrase).expect("schedule must be valid for this test");
+        let parse = |value: &str| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f").expect("datetime must be valid for this test");
+        let result: Vec<NaiveDateTime> = schedule
+            .fire_times(parse(after))
+            .take(expected.len())
+            .collect();
+        let expected: Vec<NaiveDateTime> = expected
+            .iter()
+            .map(|value| parse(value))
+            .collect();
+        assert_eq!(result, expected);
+    }
+}
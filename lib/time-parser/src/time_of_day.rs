@@ -0,0 +1,201 @@
+use chrono::NaiveTime;
+use pest::iterators::Pair;
+use pest::Parser;
+use pest_derive::Parser;
+
+use crate::datetimes::parse_fraction;
+use crate::{Error, Result};
+
+#[derive(Parser)]
+#[grammar = "grammars/clock.pest"]
+struct ClockParser;
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+
+/// Parses a wall-clock time of day such as `5pm`, `17:30`, `noon` or `quarter past 3`.
+///
+/// Unlike [`crate::parse_duration_hms`], which treats `17:30:00` as an elapsed 17.5 hours, this returns a point in the day.
+///
+/// Supported forms:
+/// - 24-hour: `17:30`, `17:30:15`, `17:30:15.250`
+/// - 12-hour: `5pm`, `5 p.m.`, `5:30am`, `12:00:01 AM`
+/// - named: `noon`, `midday`, `midnight`
+/// - phrases: `quarter past 3`, `half past 9pm`, `a quarter to 5`, `10 past 3`, `20 minutes to 6`, `3 o'clock`
+///
+/// Phrases and `o'clock` without am/pm read the hour on a 24-hour clock, so `quarter past 3` is `03:15` and `quarter to 0`
+/// wraps to `23:45`.
+#[inline(always)]
+pub fn parse_time_of_day(time: impl AsRef<str>) -> Result<NaiveTime> {
+    let time = time.as_ref();
+    let parse_result = ClockParser::parse(Rule::clock, time.trim()).map_err(|_e| Error::invalid_time(time))?;
+
+    let Some(clock_pair) = parse_result
+        .flat_map(|pair| pair.into_inner())
+        .flat_map(|pair| pair.into_inner())
+        .next()
+    else {
+        unreachable!("Clock time must have a form");
+    };
+
+    let result = match clock_pair.as_rule() {
+        Rule::clock_noon => NaiveTime::from_hms_opt(12, 0, 0),
+        Rule::clock_midnight => Some(NaiveTime::MIN),
+        Rule::clock_12h | Rule::clock_24h | Rule::clock_oclock => parse_clock(clock_pair, time)?,
+        Rule::clock_phrase => parse_phrase(clock_pair, time)?,
+        _ => unreachable!("Unexpected rule in clock time parsing: {:?}", clock_pair.as_rule()),
+    };
+
+    result.ok_or_else(|| {
+        tracing::trace!("Invalid time of day: {}", time);
+        Error::invalid_time(time)
+    })
+}
+
+#[inline(always)]
+fn parse_clock(pair: Pair<Rule>, time: &str) -> Result<Option<NaiveTime>> {
+    let requires_meridiem = pair.as_rule() == Rule::clock_12h;
+    let (mut hour, mut minute, mut second, mut nano, mut meridiem) = (0, 0, 0, 0, None);
+
+    for inner_pair in pair.into_inner() {
+        match inner_pair.as_rule() {
+            Rule::clock_hour => hour = parse_number(&inner_pair, time)?,
+            Rule::clock_minute => minute = parse_number(&inner_pair, time)?,
+            Rule::clock_second => second = parse_number(&inner_pair, time)?,
+            Rule::clock_fraction => nano = parse_fraction(inner_pair.as_str()),
+            Rule::clock_am | Rule::clock_pm => meridiem = Some(inner_pair.as_rule()),
+            _ => unreachable!("Unexpected rule in clock parsing: {:?}", inner_pair.as_rule()),
+        }
+    }
+
+    debug_assert!(!requires_meridiem || meridiem.is_some());
+    let Some(hour) = apply_meridiem(hour, meridiem) else {
+        return Ok(None);
+    };
+    Ok(NaiveTime::from_hms_nano_opt(hour, minute, second, nano))
+}
+
+#[inline(always)]
+fn parse_phrase(pair: Pair<Rule>, time: &str) -> Result<Option<NaiveTime>> {
+    let (mut minutes, mut half, mut before, mut hour, mut meridiem) = (0, false, false, 0, None);
+
+    for inner_pair in pair.into_inner() {
+        match inner_pair.as_rule() {
+            Rule::clock_phrase_minutes => {
+                for minutes_pair in inner_pair.into_inner() {
+                    match minutes_pair.as_rule() {
+                        Rule::clock_quarter => minutes = 15,
+                        Rule::clock_half => (minutes, half) = (30, true),
+                        Rule::clock_minute_count => minutes = parse_number(&minutes_pair, time)?,
+                        _ => unreachable!("Unexpected rule in clock phrase minutes: {:?}", minutes_pair.as_rule()),
+                    }
+                }
+            }
+            Rule::clock_past => before = false,
+            Rule::clock_to => before = true,
+            Rule::clock_hour => hour = parse_number(&inner_pair, time)?,
+            Rule::clock_am | Rule::clock_pm => meridiem = Some(inner_pair.as_rule()),
+            _ => unreachable!("Unexpected rule in clock phrase parsing: {:?}", inner_pair.as_rule()),
+        }
+    }
+
+    if !(1..60).contains(&minutes) || (half && before) {
+        tracing::trace!("Invalid minutes in clock phrase: {}", time);
+        return Ok(None);
+    }
+    let Some(hour) = apply_meridiem(hour, meridiem) else {
+        return Ok(None);
+    };
+
+    let total = if before { hour * 60 + MINUTES_PER_DAY - minutes } else { hour * 60 + minutes } % MINUTES_PER_DAY;
+    Ok(NaiveTime::from_hms_opt(total / 60, total % 60, 0))
+}
+
+/// Converts a 12-hour clock hour to a 24-hour one, leaving it untouched when there is no am/pm.
+#[inline(always)]
+fn apply_meridiem(hour: u32, meridiem: Option<Rule>) -> Option<u32> {
+    match meridiem {
+        None if hour < 24 => Some(hour),
+        Some(meridiem) if (1..=12).contains(&hour) => Some(hour % 12 + if meridiem == Rule::clock_pm { 12 } else { 0 }),
+        _ => {
+            tracing::trace!("Invalid clock hour: {}", hour);
+            None
+        }
+    }
+}
+
+#[inline(always)]
+fn parse_number(pair: &Pair<Rule>, time: &str) -> Result<u32> {
+    pair.as_str()
+        .parse::<u32>()
+        .map_err(|_e| Error::invalid_time(time))
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::pm("5pm", 17, 0, 0, 0)]
+    #[case::pm_spaced("5 PM", 17, 0, 0, 0)]
+    #[case::pm_dotted("5 p.m.", 17, 0, 0, 0)]
+    #[case::am_minutes("9:45am", 9, 45, 0, 0)]
+    #[case::am_seconds("9:45:30 am", 9, 45, 30, 0)]
+    #[case::midnight_12am("12am", 0, 0, 0, 0)]
+    #[case::noon_12pm("12:00pm", 12, 0, 0, 0)]
+    #[case::h24("17:30", 17, 30, 0, 0)]
+    #[case::h24_single_digit("7:05", 7, 5, 0, 0)]
+    #[case::h24_seconds("17:30:15", 17, 30, 15, 0)]
+    #[case::h24_fraction("17:30:15.25", 17, 30, 15, 250_000_000)]
+    #[case::h24_nanoseconds("00:00:00.000000001", 0, 0, 0, 1)]
+    #[case::h12_fraction("1:02:03.5pm", 13, 2, 3, 500_000_000)]
+    #[case::noon("noon", 12, 0, 0, 0)]
+    #[case::midday("Midday", 12, 0, 0, 0)]
+    #[case::midnight("midnight", 0, 0, 0, 0)]
+    #[case::quarter_past("quarter past 3", 3, 15, 0, 0)]
+    #[case::a_quarter_to("a quarter to 5", 4, 45, 0, 0)]
+    #[case::half_past_pm("half past 9pm", 21, 30, 0, 0)]
+    #[case::minutes_past("10 past 3", 3, 10, 0, 0)]
+    #[case::minutes_to("20 minutes to 6 pm", 17, 40, 0, 0)]
+    #[case::one_minute_after("1 minute after 14", 14, 1, 0, 0)]
+    #[case::quarter_to_midnight("quarter to 0", 23, 45, 0, 0)]
+    #[case::quarter_to_12am("quarter to 12am", 23, 45, 0, 0)]
+    #[case::oclock("3 o'clock", 3, 0, 0, 0)]
+    #[case::oclock_pm("3 o'clock pm", 15, 0, 0, 0)]
+    #[case::surrounding_whitespace("  17:30  ", 17, 30, 0, 0)]
+    fn test_parse_time_of_day(#[case] time: &str, #[case] hour: u32, #[case] minute: u32, #[case] second: u32, #[case] nano: u32) {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .try_init()
+            .ok();
+
+        let expected = NaiveTime::from_hms_nano_opt(hour, minute, second, nano).expect("expected time must be valid for this test");
+        match parse_time_of_day(time) {
+            Ok(actual) => assert_eq!(actual, expected),
+            Err(e) => panic!("Expected Ok, got Err: {:?}", e),
+        }
+    }
+
+    #[rstest]
+    #[case::empty("")]
+    #[case::bare_number("5")]
+    #[case::hour_out_of_range("24:00")]
+    #[case::minute_out_of_range("17:60")]
+    #[case::second_out_of_range("17:30:61")]
+    #[case::pm_hour_out_of_range("13pm")]
+    #[case::zero_am("0am")]
+    #[case::fraction_without_seconds("17:30.5")]
+    #[case::fraction_too_long("17:30:15.1234567890")]
+    #[case::half_to("half to 4")]
+    #[case::zero_minutes_past("0 past 3")]
+    #[case::sixty_minutes_past("60 past 3")]
+    #[case::phrase_hour_out_of_range("quarter past 24")]
+    #[case::duration("1h30m")]
+    fn test_parse_time_of_day_invalid(#[case] time: &str) {
+        match parse_time_of_day(time) {
+            Ok(actual) => panic!("Expected Err, got Ok {:?}", actual),
+            Err(e) => assert_eq!(e.to_string(), Error::invalid_time(time).to_string()),
+        }
+    }
+}
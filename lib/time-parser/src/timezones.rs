@@ -0,0 +1,402 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use chrono::{DateTime, FixedOffset, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use chrono_tz::Tz;
+use pest::iterators::Pair;
+use pest::Parser;
+use pest_derive::Parser;
+
+use crate::{parse_datetime_with_abbreviations, parse_time_of_day, Error, Result, SECONDS_PER_DAY, SECONDS_PER_HOUR, SECONDS_PER_MINUTE};
+
+#[derive(Parser)]
+#[grammar = "grammars/timezones.pest"]
+struct TimeZoneParser;
+
+/// A parsed time zone: either a fixed UTC offset or an IANA zone from the bundled tz database.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Zone {
+    Offset(FixedOffset),
+    Named(Tz),
+}
+
+impl Zone {
+    /// Attaches this zone to a wall-clock time.
+    ///
+    /// Times repeated by a DST transition resolve to the earlier instant; times skipped by one return `None`.
+    pub fn localize(&self, datetime: NaiveDateTime) -> Option<DateTime<FixedOffset>> {
+        match self {
+            Self::Offset(offset) => offset.from_local_datetime(&datetime).single(),
+            Self::Named(tz) => match tz.from_local_datetime(&datetime) {
+                LocalResult::Single(datetime) | LocalResult::Ambiguous(datetime, _) => Some(datetime.fixed_offset()),
+                LocalResult::None => None,
+            },
+        }
+    }
+
+    /// The first instant of `date` in this zone: midnight, or the end of the DST gap when midnight is skipped.
+    pub fn start_of_day(&self, date: NaiveDate) -> Option<DateTime<FixedOffset>> {
+        let midnight = date.and_time(NaiveTime::MIN);
+        if let Some(start) = self.localize(midnight) {
+            return Some(start);
+        }
+        let Self::Named(tz) = self else {
+            return None;
+        };
+
+        // Search the instants within a day of midnight for the first one whose local date is `date`.
+        let local_date = |timestamp: i64| DateTime::from_timestamp(timestamp, 0).map(|utc| utc.with_timezone(tz).date_naive());
+        let midnight = midnight.and_utc().timestamp();
+        let (mut before, mut after) = (midnight - SECONDS_PER_DAY as i64, midnight + SECONDS_PER_DAY as i64);
+        while after - before > 1 {
+            let middle = before + (after - before) / 2;
+            if local_date(middle)? < date {
+                before = middle;
+            } else {
+                after = middle;
+            }
+        }
+        DateTime::from_timestamp(after, 0).map(|utc| utc.with_timezone(tz).fixed_offset())
+    }
+}
+
+impl fmt::Display for Zone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Offset(offset) => write!(f, "{}", offset),
+            Self::Named(tz) => f.write_str(tz.name()),
+        }
+    }
+}
+
+const DEFAULT_ABBREVIATIONS: &[(&str, i32)] = &[
+    ("UTC", 0),
+    ("UT", 0),
+    ("GMT", 0),
+    ("Z", 0),
+    ("WET", 0),
+    ("WEST", 60),
+    ("BST", 60),
+    ("CET", 60),
+    ("CEST", 120),
+    ("EET", 120),
+    ("EEST", 180),
+    ("MSK", 180),
+    ("IST", 330),
+    ("SGT", 480),
+    ("AWST", 480),
+    ("JST", 540),
+    ("KST", 540),
+    ("ACST", 570),
+    ("AEST", 600),
+    ("AEDT", 660),
+    ("NZST", 720),
+    ("NZDT", 780),
+    ("HST", -600),
+    ("AKST", -540),
+    ("AKDT", -480),
+    ("PST", -480),
+    ("PDT", -420),
+    ("MST", -420),
+    ("MDT", -360),
+    ("CST", -360),
+    ("CDT", -300),
+    ("EST", -300),
+    ("EDT", -240),
+    ("AST", -240),
+    ("ADT", -180),
+];
+
+/// The table used to resolve time zone abbreviations such as `PST`.
+///
+/// Abbreviations are ambiguous: `IST` is India, Ireland or Israel and `CST` is US Central or China.  The default table
+/// maps `IST` to India (+05:30) and `CST` to US Central (-06:00); override entries with [`Self::insert`] as needed.
+/// Lookups are case-insensitive and take precedence over IANA names.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TimeZoneAbbreviations {
+    zones: HashMap<String, Zone>,
+}
+
+impl Default for TimeZoneAbbreviations {
+    fn default() -> Self {
+        let zones = DEFAULT_ABBREVIATIONS
+            .iter()
+            .map(|(abbreviation, minutes)| {
+                let offset = FixedOffset::east_opt(minutes * SECONDS_PER_MINUTE as i32).expect("default abbreviations must be in range");
+                (abbreviation.to_string(), Zone::Offset(offset))
+            })
+            .collect();
+        Self { zones }
+    }
+}
+
+/// The default table, built once for parsers that take no table.
+pub(crate) fn default_abbreviations() -> &'static TimeZoneAbbreviations {
+    static DEFAULT: OnceLock<TimeZoneAbbreviations> = OnceLock::new();
+    DEFAULT.get_or_init(TimeZoneAbbreviations::default)
+}
+
+impl TimeZoneAbbreviations {
+    /// An empty table, for callers that want to list every abbreviation themselves.
+    pub fn empty() -> Self {
+        Self { zones: HashMap::new() }
+    }
+
+    pub fn insert(&mut self, abbreviation: impl AsRef<str>, zone: Zone) -> Option<Zone> {
+        self.zones
+            .insert(abbreviation.as_ref().to_ascii_uppercase(), zone)
+    }
+
+    pub fn remove(&mut self, abbreviation: impl AsRef<str>) -> Option<Zone> {
+        self.zones
+            .remove(&abbreviation.as_ref().to_ascii_uppercase())
+    }
+
+    pub fn get(&self, abbreviation: impl AsRef<str>) -> Option<Zone> {
+        self.zones
+            .get(&abbreviation.as_ref().to_ascii_uppercase())
+            .copied()
+    }
+}
+
+/// Parses a time zone: a numeric offset (`+05:30`, `-0800`, `UTC+5:30`, `GMT-8`), an abbreviation from `abbreviations`
+/// (`PST`) or an IANA name (`Europe/Berlin`).
+#[inline(always)]
+pub fn parse_timezone(input: impl AsRef<str>, abbreviations: &TimeZoneAbbreviations) -> Result<Zone> {
+    let input = input.as_ref();
+    let parse_result = TimeZoneParser::parse(Rule::timezone, input.trim()).map_err(|_e| Error::invalid_time_zone(input))?;
+
+    let Some(pair) = parse_result
+        .flat_map(|pair| pair.into_inner())
+        .next()
+    else {
+        unreachable!("Time zone must have a form");
+    };
+
+    match pair.as_rule() {
+        Rule::tz_offset => parse_offset(pair, input).map(Zone::Offset),
+        Rule::tz_name => {
+            let name = pair.as_str();
+            if let Some(zone) = abbreviations.get(name) {
+                return Ok(zone);
+            }
+            Tz::from_str(name).map(Zone::Named).map_err(|_e| {
+                tracing::trace!("Unknown time zone name: {}", name);
+                Error::invalid_time_zone(input)
+            })
+        }
+        _ => unreachable!("Unexpected rule in time zone parsing: {:?}", pair.as_rule()),
+    }
+}
+
+/// Parses a datetime with a time zone, such as `2024-10-18 17:00 Europe/Berlin` or `2024-10-18T09:00 PST`.
+///
+/// Any format accepted by [`crate::parse_datetime`] works, followed by a zone accepted by [`parse_timezone`].  Inputs
+/// that already carry an offset are returned as-is; inputs with neither an offset nor a zone are rejected.
+#[inline(always)]
+pub fn parse_zoned_datetime(input: impl AsRef<str>, abbreviations: &TimeZoneAbbreviations) -> Result<DateTime<FixedOffset>> {
+    let input = input.as_ref();
+    if let Some(datetime) = parse_datetime_with_abbreviations(input, abbreviations)
+        .ok()
+        .and_then(|parsed| parsed.with_offset())
+    {
+        return Ok(datetime);
+    }
+
+    let (datetime, zone) = split_zone(input, abbreviations).ok_or_else(|| Error::invalid_time(input))?;
+    let parsed = parse_datetime_with_abbreviations(datetime, abbreviations).map_err(|_e| Error::invalid_time(input))?;
+    if parsed.offset.is_some() {
+        tracing::trace!("Datetime has both an offset and a time zone: {}", input);
+        return Err(Error::invalid_time(input));
+    }
+
+    zone.localize(parsed.datetime).ok_or_else(|| {
+        tracing::trace!("Datetime does not exist in time zone {}: {}", zone, input);
+        Error::invalid_time(input)
+    })
+}
+
+/// Parses a time of day with a time zone, such as `09:00 PST`, `5pm Europe/Berlin` or `10:00 UTC+5:30`, on `date`.
+///
+/// The date is needed to pick the right offset for IANA zones that observe daylight saving time.
+#[inline(always)]
+pub fn parse_zoned_time_of_day(input: impl AsRef<str>, date: NaiveDate, abbreviations: &TimeZoneAbbreviations) -> Result<DateTime<FixedOffset>> {
+    let input = input.as_ref();
+    let (time, zone) = split_zone(input, abbreviations).ok_or_else(|| Error::invalid_time(input))?;
+    let time = parse_time_of_day(time).map_err(|_e| Error::invalid_time(input))?;
+
+    zone.localize(date.and_time(time)).ok_or_else(|| {
+        tracing::trace!("Time does not exist in time zone {}: {}", zone, input);
+        Error::invalid_time(input)
+    })
+}
+
+/// Splits the trailing time zone from `input`, returning the remainder and the zone.
+#[inline(always)]
+fn split_zone<'a>(input: &'a str, abbreviations: &TimeZoneAbbreviations) -> Option<(&'a str, Zone)> {
+    let (rest, zone) = input.trim().rsplit_once(char::is_whitespace)?;
+    let zone = parse_timezone(zone, abbreviations).ok()?;
+    Some((rest.trim_end(), zone))
+}
+
+#[inline(always)]
+fn parse_offset(pair: Pair<Rule>, input: &str) -> Result<FixedOffset> {
+    let mut negative = false;
+    let mut seconds = 0;
+
+    for inner_pair in pair.into_inner() {
+        let value = || {
+            inner_pair
+                .as_str()
+                .parse::<i32>()
+                .map_err(|_e| Error::invalid_time_zone(input))
+        };
+        match inner_pair.as_rule() {
+            Rule::tz_utc => {}
+            Rule::tz_sign => negative = inner_pair.as_str() == "-",
+            Rule::tz_hour => seconds += value()? * SECONDS_PER_HOUR as i32,
+            Rule::tz_minute => {
+                let minutes = value()?;
+                if minutes >= 60 {
+                    tracing::trace!("Invalid offset minutes: {}", minutes);
+                    return Err(Error::invalid_time_zone(input));
+                }
+                seconds += minutes * SECONDS_PER_MINUTE as i32;
+            }
+            _ => unreachable!("Unexpected rule in offset parsing: {:?}", inner_pair.as_rule()),
+        }
+    }
+
+    FixedOffset::east_opt(if negative { -seconds } else { seconds }).ok_or_else(|| {
+        tracing::trace!("Offset out of range: {}", input);
+        Error::invalid_time_zone(input)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    fn offset(hours: i32, minutes: i32) -> Zone {
+        let seconds = hours * SECONDS_PER_HOUR as i32 + hours.signum() * minutes * SECONDS_PER_MINUTE as i32;
+        Zone::Offset(FixedOffset::east_opt(seconds).expect("offset must be valid for this test"))
+    }
+
+    fn rfc3339(value: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(value).expect("datetime must be valid for this test")
+    }
+
+    #[rstest]
+    #[case::extended("+05:30", offset(5, 30))]
+    #[case::basic("-0800", offset(-8, 0))]
+    #[case::hour_only("-8", offset(-8, 0))]
+    #[case::utc_prefix("UTC+5:30", offset(5, 30))]
+    #[case::gmt_prefix("GMT-8", offset(-8, 0))]
+    #[case::utc("UTC", offset(0, 0))]
+    #[case::zulu("z", offset(0, 0))]
+    #[case::pst("PST", offset(-8, 0))]
+    #[case::lowercase_abbreviation("cest", offset(2, 0))]
+    #[case::ist("IST", offset(5, 30))]
+    #[case::iana("Europe/Berlin", Zone::Named(Tz::Europe__Berlin))]
+    #[case::iana_underscore("America/New_York", Zone::Named(Tz::America__New_York))]
+    #[case::iana_etc("Etc/GMT+5", Zone::Named(Tz::Etc__GMTPlus5))]
+    fn test_parse_timezone(#[case] input: &str, #[case] expected: Zone) {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .try_init()
+            .ok();
+
+        match parse_timezone(input, &TimeZoneAbbreviations::default()) {
+            Ok(actual) => assert_eq!(actual, expected),
+            Err(e) => panic!("Expected Ok, got Err: {:?}", e),
+        }
+    }
+
+    #[rstest]
+    #[case::empty("")]
+    #[case::unknown_abbreviation("XYZ")]
+    #[case::unknown_name("Mars/Olympus_Mons")]
+    #[case::wrong_case_name("europe/berlin")]
+    #[case::minutes_out_of_range("+05:60")]
+    #[case::hours_out_of_range("+24:00")]
+    #[case::missing_sign("0530")]
+    fn test_parse_timezone_invalid(#[case] input: &str) {
+        match parse_timezone(input, &TimeZoneAbbreviations::default()) {
+            Ok(actual) => panic!("Expected Err, got Ok {:?}", actual),
+            Err(e) => assert_eq!(e.to_string(), Error::invalid_time_zone(input).to_string()),
+        }
+    }
+
+    #[test]
+    fn test_custom_abbreviations() {
+        let mut abbreviations = TimeZoneAbbreviations::default();
+        assert_eq!(abbreviations.insert("ist", offset(2, 0)), Some(offset(5, 30)));
+        abbreviations.insert("CT", Zone::Named(Tz::America__Chicago));
+
+        assert_eq!(parse_timezone("IST", &abbreviations).ok(), Some(offset(2, 0)));
+        assert_eq!(parse_timezone("CT", &abbreviations).ok(), Some(Zone::Named(Tz::America__Chicago)));
+        assert!(parse_timezone("PST", &TimeZoneAbbreviations::empty()).is_err());
+    }
+
+    #[rstest]
+    #[case::offset_included("2024-10-18T12:00:01+02:00", "2024-10-18T12:00:01+02:00")]
+    #[case::iana_summer("2024-07-01 17:00 Europe/Berlin", "2024-07-01T17:00:00+02:00")]
+    #[case::iana_winter("2024-12-01 17:00 Europe/Berlin", "2024-12-01T17:00:00+01:00")]
+    #[case::abbreviation("2024-10-18T09:00 PST", "2024-10-18T09:00:00-08:00")]
+    #[case::numeric("2024-10-18 10:00:00 UTC+5:30", "2024-10-18T10:00:00+05:30")]
+    #[case::dst_overlap_earliest("2024-11-03 01:30 America/New_York", "2024-11-03T01:30:00-04:00")]
+    fn test_parse_zoned_datetime(#[case] input: &str, #[case] expected: &str) {
+        match parse_zoned_datetime(input, &TimeZoneAbbreviations::default()) {
+            Ok(actual) => {
+                assert_eq!(actual, rfc3339(expected));
+                assert_eq!(actual.offset(), rfc3339(expected).offset());
+            }
+            Err(e) => panic!("Expected Ok, got Err: {:?}", e),
+        }
+    }
+
+    #[rstest]
+    #[case::no_zone("2024-10-18 17:00")]
+    #[case::unknown_zone("2024-10-18 17:00 Mars/Olympus_Mons")]
+    #[case::offset_and_zone("2024-10-18T17:00+02:00 Europe/Berlin")]
+    #[case::dst_gap("2024-03-10 02:30 America/New_York")]
+    #[case::invalid_datetime("2024-13-18 17:00 UTC")]
+    fn test_parse_zoned_datetime_invalid(#[case] input: &str) {
+        match parse_zoned_datetime(input, &TimeZoneAbbreviations::default()) {
+            Ok(actual) => panic!("Expected Err, got Ok {:?}", actual),
+            Err(e) => assert_eq!(e.to_string(), Error::invalid_time(input).to_string()),
+        }
+    }
+
+    #[rstest]
+    #[case::abbreviation("09:00 PST", "2024-10-18T09:00:00-08:00")]
+    #[case::iana("17:00 Europe/Berlin", "2024-10-18T17:00:00+02:00")]
+    #[case::numeric("10:00 UTC+5:30", "2024-10-18T10:00:00+05:30")]
+    #[case::twelve_hour("5pm America/New_York", "2024-10-18T17:00:00-04:00")]
+    #[case::phrase("quarter past 3 UTC", "2024-10-18T03:15:00+00:00")]
+    fn test_parse_zoned_time_of_day(#[case] input: &str, #[case] expected: &str) {
+        let date = NaiveDate::from_ymd_opt(2024, 10, 18).expect("date must be valid for this test");
+        match parse_zoned_time_of_day(input, date, &TimeZoneAbbreviations::default()) {
+            Ok(actual) => {
+                assert_eq!(actual, rfc3339(expected));
+                assert_eq!(actual.offset(), rfc3339(expected).offset());
+            }
+            Err(e) => panic!("Expected Ok, got Err: {:?}", e),
+        }
+    }
+
+    #[rstest]
+    #[case::no_zone("09:00")]
+    #[case::invalid_time("25:00 UTC")]
+    fn test_parse_zoned_time_of_day_invalid(#[case] input: &str) {
+        let date = NaiveDate::from_ymd_opt(2024, 10, 18).expect("date must be valid for this test");
+        match parse_zoned_time_of_day(input, date, &TimeZoneAbbreviations::default()) {
+            Ok(actual) => panic!("Expected Err, got Ok {:?}", actual),
+            Err(e) => assert_eq!(e.to_string(), Error::invalid_time(input).to_string()),
+        }
+    }
+}
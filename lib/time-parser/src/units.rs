@@ -0,0 +1,211 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::{Conversion, Error, Result, NANOSECONDS_PER_MICROSECOND, NANOSECONDS_PER_MILLISECOND, SECONDS_PER_DAY, SECONDS_PER_HOUR, SECONDS_PER_MINUTE, SECONDS_PER_WEEK};
+
+const NANOSECONDS_PER_SECOND: u64 = 1_000_000_000;
+
+/// A unit of the shorthand duration syntax, ordered from shortest to longest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Unit {
+    Nanoseconds,
+    Microseconds,
+    Milliseconds,
+    Seconds,
+    Minutes,
+    Hours,
+    Days,
+    Weeks,
+}
+
+impl Unit {
+    /// Every unit, from shortest to longest.
+    pub const ALL: [Unit; 8] = [
+        Self::Nanoseconds,
+        Self::Microseconds,
+        Self::Milliseconds,
+        Self::Seconds,
+        Self::Minutes,
+        Self::Hours,
+        Self::Days,
+        Self::Weeks,
+    ];
+
+    /// The symbol [`crate::duration_as_string`] writes, such as `h`.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::Nanoseconds => "ns",
+            Self::Microseconds => "µs",
+            Self::Milliseconds => "ms",
+            Self::Seconds => "s",
+            Self::Minutes => "m",
+            Self::Hours => "h",
+            Self::Days => "d",
+            Self::Weeks => "w",
+        }
+    }
+
+    /// Every spelling the parser accepts, longest first, as listed in the `unit` rule of `grammars/intervals.pest`.
+    pub fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            Self::Nanoseconds => &["nanoseconds", "nanosecond", "ns", "n"],
+            Self::Microseconds => &["microseconds", "microsecond", "us", "µs", "u", "µ"],
+            Self::Milliseconds => &["milliseconds", "millisecond", "ms"],
+            Self::Seconds => &["seconds", "second", "sec", "s"],
+            Self::Minutes => &["minutes", "minute", "min", "m"],
+            Self::Hours => &["hours", "hour", "hr", "h"],
+            Self::Days => &["days", "day", "d"],
+            Self::Weeks => &["weeks", "week", "w"],
+        }
+    }
+
+    /// The length of one unit in nanoseconds.
+    pub fn nanoseconds(&self) -> u64 {
+        match self {
+            Self::Nanoseconds => 1,
+            Self::Microseconds => NANOSECONDS_PER_MICROSECOND,
+            Self::Milliseconds => NANOSECONDS_PER_MILLISECOND,
+            Self::Seconds => NANOSECONDS_PER_SECOND,
+            Self::Minutes => SECONDS_PER_MINUTE * NANOSECONDS_PER_SECOND,
+            Self::Hours => SECONDS_PER_HOUR * NANOSECONDS_PER_SECOND,
+            Self::Days => SECONDS_PER_DAY * NANOSECONDS_PER_SECOND,
+            Self::Weeks => SECONDS_PER_WEEK * NANOSECONDS_PER_SECOND,
+        }
+    }
+
+    /// One unit as a [`Duration`].
+    pub fn duration(&self) -> Duration {
+        Duration::from_nanos(self.nanoseconds())
+    }
+
+    /// The next shorter unit, or `None` for nanoseconds.
+    pub fn smaller(&self) -> Option<Unit> {
+        let index = Self::ALL.iter().position(|unit| unit == self)?;
+        index.checked_sub(1).map(|index| Self::ALL[index])
+    }
+
+    /// The next longer unit, or `None` for weeks.
+    pub fn larger(&self) -> Option<Unit> {
+        let index = Self::ALL.iter().position(|unit| unit == self)?;
+        Self::ALL.get(index + 1).copied()
+    }
+
+    /// `value` of this unit, or `None` when it does not fit in a [`Duration`].
+    pub fn checked_duration(&self, value: u64) -> Option<Duration> {
+        let nanoseconds = value as u128 * self.nanoseconds() as u128;
+        let seconds = u64::try_from(nanoseconds / NANOSECONDS_PER_SECOND as u128).ok()?;
+        Some(Duration::new(seconds, (nanoseconds % NANOSECONDS_PER_SECOND as u128) as u32))
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+impl FromStr for Unit {
+    type Err = Error;
+
+    /// Parses any of the unit's [`aliases`](Unit::aliases), case-sensitively as the parser does.
+    fn from_str(value: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|unit| unit.aliases().contains(&value))
+            .ok_or_else(|| Error::invalid_unit(value))
+    }
+}
+
+/// Expresses durations in a chosen [`Unit`].
+pub trait DurationUnits {
+    /// The duration as a possibly fractional number of units.
+    fn as_unit_f64(&self, unit: Unit) -> f64;
+
+    /// The duration as an exact fraction of units.
+    fn as_unit_exact(&self, unit: Unit) -> Conversion;
+}
+
+impl DurationUnits for Duration {
+    fn as_unit_f64(&self, unit: Unit) -> f64 {
+        self.as_unit_exact(unit).as_f64()
+    }
+
+    fn as_unit_exact(&self, unit: Unit) -> Conversion {
+        Conversion::new(*self, unit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[test]
+    fn test_aliases_match_grammar() {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .try_init()
+            .ok();
+
+        for unit in Unit::ALL {
+            for alias in unit.aliases() {
+                assert_eq!(alias.parse::<Unit>().ok(), Some(unit), "alias {} must parse", alias);
+                assert_eq!(
+                    crate::parse_duration(format!("3{}", alias)).ok(),
+                    unit.checked_duration(3),
+                    "alias {} must be accepted by the parser",
+                    alias
+                );
+            }
+        }
+    }
+
+    #[rstest]
+    #[case::symbol("h", Some(Unit::Hours))]
+    #[case::long("milliseconds", Some(Unit::Milliseconds))]
+    #[case::micro_sign("µs", Some(Unit::Microseconds))]
+    #[case::unknown("fortnight", None)]
+    #[case::uppercase("H", None)]
+    #[case::empty("", None)]
+    fn test_from_str(#[case] input: &str, #[case] expected: Option<Unit>) {
+        match input.parse::<Unit>() {
+            Ok(actual) => assert_eq!(Some(actual), expected),
+            Err(e) => {
+                assert_eq!(expected, None);
+                assert_eq!(e.to_string(), Error::invalid_unit(input).to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn test_ordering() {
+        assert!(Unit::Nanoseconds < Unit::Microseconds);
+        assert!(Unit::Days < Unit::Weeks);
+        assert!(Unit::ALL
+            .windows(2)
+            .all(|pair| pair[0].nanoseconds() < pair[1].nanoseconds()));
+        assert_eq!(Unit::Hours.smaller(), Some(Unit::Minutes));
+        assert_eq!(Unit::Hours.larger(), Some(Unit::Days));
+        assert_eq!(Unit::Nanoseconds.smaller(), None);
+        assert_eq!(Unit::Weeks.larger(), None);
+    }
+
+    #[rstest]
+    #[case::minutes_in_hours(Duration::from_secs(5400), Unit::Hours, 1.5, (3, 2))]
+    #[case::week_in_days(Duration::from_secs(SECONDS_PER_WEEK), Unit::Days, 7.0, (7, 1))]
+    #[case::millis_in_seconds(Duration::from_millis(250), Unit::Seconds, 0.25, (1, 4))]
+    #[case::zero(Duration::ZERO, Unit::Weeks, 0.0, (0, 1))]
+    fn test_duration_units(#[case] duration: Duration, #[case] unit: Unit, #[case] expected: f64, #[case] ratio: (u128, u128)) {
+        assert!((duration.as_unit_f64(unit) - expected).abs() < f64::EPSILON);
+        assert_eq!(duration.as_unit_exact(unit).ratio(), ratio);
+        assert_eq!(duration.as_unit_exact(unit).unit(), unit);
+    }
+
+    #[test]
+    fn test_checked_duration() {
+        assert_eq!(Unit::Milliseconds.checked_duration(5000), Some(Duration::from_secs(5)));
+        assert_eq!(Unit::Weeks.checked_duration(u64::MAX), None);
+    }
+}